@@ -0,0 +1,465 @@
+//! Derive macros for [clean_cli](https://crates.io/crates/clean_cli).
+//!
+//! Use them through `derive` feature of `clean_cli` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Expr, Fields,
+    GenericArgument, Ident, LitStr, PathArguments, Result, Type,
+};
+
+/// Implement `clean_cli::CleanArgs` for struct with named fields.
+/// See `CleanArgs` trait for supported field types and attributes.
+///
+/// Attributes are checked against field types, e.g. `count` requires `i64` field:
+///
+/// ```compile_fail
+/// #[derive(clean_cli::CleanArgs)]
+/// struct Args {
+///     #[clean(count)]
+///     verbose: u8,
+/// }
+/// ```
+#[proc_macro_derive(CleanArgs, attributes(clean))]
+pub fn derive_clean_args(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    clean_args(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implement `clean_cli::CleanCommands` for enum.
+/// See `CleanCommands` trait for supported variants and attributes.
+#[proc_macro_derive(CleanCommands, attributes(clean))]
+pub fn derive_clean_commands(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    clean_commands(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn clean_args(input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "struct with named fields expected",
+                ))
+            }
+        },
+        _ => return Err(Error::new(input.span(), "struct expected")),
+    };
+
+    let mut parameters = Vec::with_capacity(fields.len());
+    let mut readers = Vec::with_capacity(fields.len());
+    read_fields(fields, &mut parameters, &mut readers)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::clean_cli::CleanArgs for #name #ty_generics #where_clause {
+            fn parameters() -> ::std::vec::Vec<::clean_cli::ParameterBuilder<'static>> {
+                ::std::vec![#(#parameters),*]
+            }
+
+            fn from_context<T: ::clean_cli::Config>(
+                unit: &::clean_cli::ContextUnit<'_, T>,
+            ) -> ::std::result::Result<Self, ::clean_cli::ValueError> {
+                ::std::result::Result::Ok(Self { #(#readers),* })
+            }
+        }
+    })
+}
+
+fn read_fields<'a>(
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+    parameters: &mut Vec<TokenStream2>,
+    readers: &mut Vec<TokenStream2>,
+) -> Result<()> {
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let field = Field::parse(ident.to_string(), &field.ty, &field.attrs)?;
+        parameters.push(field.parameter()?);
+        let reader = field.reader();
+        readers.push(quote! { #ident: #reader });
+    }
+    Ok(())
+}
+
+fn clean_commands(input: DeriveInput) -> Result<TokenStream2> {
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return Err(Error::new(input.span(), "enum expected")),
+    };
+
+    let mut builders = Vec::with_capacity(variants.len());
+    let mut arms = Vec::with_capacity(variants.len());
+    for variant in variants {
+        let command = Command::parse(variant)?;
+        let name = &command.name;
+        let ident = &variant.ident;
+        let mut builder = quote! { ::clean_cli::CommandBuilder::with_name(#name) };
+        if !command.description.is_empty() {
+            let description = &command.description;
+            builder.extend(quote! { .description(#description) });
+        }
+        for alias in &command.aliases {
+            builder.extend(quote! { .alias(#alias) });
+        }
+
+        let value = match &variant.fields {
+            Fields::Unit => quote! { Self::#ident },
+            Fields::Named(fields) => {
+                let mut parameters = Vec::new();
+                let mut readers = Vec::new();
+                read_fields(&fields.named, &mut parameters, &mut readers)?;
+                builder.extend(quote! { #(.parameter(#parameters))* });
+                quote! {{
+                    let unit = &units[0];
+                    Self::#ident { #(#readers),* }
+                }}
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                match command.subcommands {
+                    true => {
+                        builder = quote! {
+                            <#ty as ::clean_cli::CleanCommands>::commands(handler)
+                                .into_iter()
+                                .fold(#builder, |cmd, sub| cmd.subcommand(sub))
+                        };
+                        quote! {
+                            Self::#ident(<#ty as ::clean_cli::CleanCommands>::from_units(&units[1..])?)
+                        }
+                    }
+                    false => {
+                        builder.extend(quote! { .args::<#ty>() });
+                        quote! {
+                            Self::#ident(<#ty as ::clean_cli::CleanArgs>::from_context(&units[0])?)
+                        }
+                    }
+                }
+            }
+            Fields::Unnamed(fields) => {
+                return Err(Error::new(
+                    fields.span(),
+                    "variant with single field expected",
+                ))
+            }
+        };
+        if !command.subcommands {
+            builder.extend(quote! { .handler(::std::clone::Clone::clone(handler)) });
+        }
+        builders.push(builder);
+        arms.push(quote! { #name => #value, });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::clean_cli::CleanCommands for #name #ty_generics #where_clause {
+            fn commands<T, F>(handler: &F) -> ::std::vec::Vec<::clean_cli::CommandBuilder<T>>
+            where
+                T: ::clean_cli::Config,
+                F: ::std::ops::Fn(::clean_cli::Context<T>) -> T::Result
+                    + ::std::clone::Clone
+                    + 'static,
+            {
+                ::std::vec![#(#builders),*]
+            }
+
+            fn from_units<T: ::clean_cli::Config>(
+                units: &[::clean_cli::ContextUnit<'_, T>],
+            ) -> ::std::result::Result<Self, ::clean_cli::ValueError> {
+                let name = match units.first() {
+                    ::std::option::Option::Some(unit) => unit.command_name(),
+                    ::std::option::Option::None => {
+                        return ::std::result::Result::Err(::clean_cli::ValueError::Missing(
+                            ::std::string::String::from("command"),
+                        ))
+                    }
+                };
+                ::std::result::Result::Ok(match name {
+                    #(#arms)*
+                    name => {
+                        return ::std::result::Result::Err(::clean_cli::ValueError::Missing(
+                            ::std::string::String::from(name),
+                        ))
+                    }
+                })
+            }
+        }
+    })
+}
+
+struct Command {
+    name: String,
+    aliases: Vec<LitStr>,
+    description: String,
+    subcommands: bool,
+}
+
+impl Command {
+    fn parse(variant: &syn::Variant) -> Result<Self> {
+        let mut command = Command {
+            name: kebab_case(&variant.ident),
+            aliases: Vec::new(),
+            description: doc(&variant.attrs),
+            subcommands: false,
+        };
+        for attr in &variant.attrs {
+            if attr.path().is_ident("clean") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("name") {
+                        command.name = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.path.is_ident("alias") {
+                        command.aliases.push(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("subcommands") {
+                        command.subcommands = true;
+                    } else {
+                        return Err(meta.error("unknown clean attribute"));
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+        Ok(command)
+    }
+}
+
+/// How field value is stored in parameter
+enum Kind {
+    Flag,
+    Single,
+    Optional,
+    List,
+}
+
+struct Field<'a> {
+    name: String,
+    ty: &'a Type,
+    inner: &'a Type,
+    kind: Kind,
+    aliases: Vec<LitStr>,
+    description: String,
+    default: Option<Expr>,
+    value_type: Option<Expr>,
+    /// Span of `count` attribute
+    count: Option<Span>,
+    from_str: bool,
+}
+
+impl<'a> Field<'a> {
+    fn parse(ident: String, ty: &'a Type, attrs: &[Attribute]) -> Result<Self> {
+        let (kind, inner) = if let Some(inner) = generic_argument(ty, "Option") {
+            (Kind::Optional, inner)
+        } else if let Some(inner) = generic_argument(ty, "Vec") {
+            (Kind::List, inner)
+        } else if type_name(ty).as_deref() == Some("bool") {
+            (Kind::Flag, ty)
+        } else {
+            (Kind::Single, ty)
+        };
+
+        let mut field = Field {
+            name: ident.trim_start_matches("r#").replace('_', "-"),
+            ty,
+            inner,
+            kind,
+            aliases: Vec::new(),
+            description: doc(attrs),
+            default: None,
+            value_type: None,
+            count: None,
+            from_str: false,
+        };
+
+        for attr in attrs {
+            if attr.path().is_ident("clean") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("name") {
+                        field.name = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.path.is_ident("alias") {
+                        field.aliases.push(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("default") {
+                        field.default = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("value_type") {
+                        field.value_type = Some(meta.value()?.parse()?);
+                    } else if meta.path.is_ident("count") {
+                        field.count = Some(meta.path.span());
+                    } else if meta.path.is_ident("from_str") {
+                        field.from_str = true;
+                    } else {
+                        return Err(meta.error("unknown clean attribute"));
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+
+        if field.from_str && matches!(field.kind, Kind::List) {
+            return Err(Error::new(ty.span(), "from_str is not supported for Vec"));
+        }
+        if let Some(span) = field.count {
+            if !matches!(field.kind, Kind::Single) || type_name(ty).as_deref() != Some("i64") {
+                return Err(Error::new(span, "count requires an i64 field"));
+            }
+        }
+
+        Ok(field)
+    }
+
+    fn value_type(&self) -> Result<TokenStream2> {
+        let inner = self.inner;
+        if let Some(value_type) = &self.value_type {
+            return Ok(quote! { #value_type });
+        }
+        if self.count.is_some() {
+            return Ok(quote! { ::clean_cli::ArgType::Count });
+        }
+        if self.from_str {
+            return Ok(quote! {
+                ::clean_cli::ArgType::custom(::clean_cli::FromStrParser::<#inner>::new())
+            });
+        }
+        match type_name(inner).as_deref() {
+            Some("bool") => Ok(quote! { ::clean_cli::ArgType::Bool }),
+            Some("i64") => Ok(quote! { ::clean_cli::ArgType::Int }),
+            Some("f64") => Ok(quote! { ::clean_cli::ArgType::Float }),
+            Some("String") => Ok(quote! { ::clean_cli::ArgType::String }),
+            _ => Err(Error::new(
+                inner.span(),
+                "unsupported type, use #[clean(from_str)] or #[clean(value_type = ...)]",
+            )),
+        }
+    }
+
+    fn parameter(&self) -> Result<TokenStream2> {
+        let name = &self.name;
+        let value_type = self.value_type()?;
+        let aliases = &self.aliases;
+        let mut builder = quote! {
+            ::clean_cli::Parameter::with_name(#name)
+                .value_type(#value_type)
+                #(.alias(#aliases))*
+        };
+        if !self.description.is_empty() {
+            let description = &self.description;
+            builder.extend(quote! { .description(#description) });
+        }
+        if let Some(default) = &self.default {
+            let ty = match self.kind {
+                Kind::List => self.ty,
+                _ => self.inner,
+            };
+            builder.extend(quote_spanned! { default.span() =>
+                .default_value(::clean_cli::ArgValue::from(<#ty>::from(#default)))
+            });
+        } else if let (Kind::Single, false) = (&self.kind, self.count.is_some()) {
+            builder.extend(quote! { .required() });
+        }
+        if let Kind::List = self.kind {
+            builder.extend(quote! { .multiple() });
+        }
+        Ok(builder)
+    }
+
+    fn reader(&self) -> TokenStream2 {
+        let name = &self.name;
+        let ty = self.ty;
+        let inner = self.inner;
+        let get = match self.from_str {
+            true => quote! { unit.get_custom::<#inner>(#name).map(::std::clone::Clone::clone) },
+            false => quote! { unit.get::<#inner>(#name) },
+        };
+        match self.kind {
+            Kind::Flag => quote! { unit.get_or::<bool>(#name, false)? },
+            Kind::Single if self.count.is_some() => quote! { unit.get_or::<i64>(#name, 0)? },
+            Kind::Single => quote! { #get? },
+            Kind::Optional => quote! {
+                match #get {
+                    ::std::result::Result::Ok(v) => ::std::option::Option::Some(v),
+                    ::std::result::Result::Err(::clean_cli::ValueError::Missing(_)) => {
+                        ::std::option::Option::None
+                    }
+                    ::std::result::Result::Err(e) => return ::std::result::Result::Err(e),
+                }
+            },
+            Kind::List => quote! { unit.get_or::<#ty>(#name, ::std::vec::Vec::new())? },
+        }
+    }
+}
+
+/// Doc comment lines joined into one line
+fn doc(attrs: &[Attribute]) -> String {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+        if let syn::Meta::NameValue(meta) = &attr.meta {
+            if let Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) = &meta.value
+            {
+                let line = s.value();
+                if !line.trim().is_empty() {
+                    lines.push(line.trim().to_string());
+                }
+            }
+        }
+    }
+    lines.join(" ")
+}
+
+/// `CamelCase` identifier in `kebab-case`
+fn kebab_case(ident: &Ident) -> String {
+    let mut name = String::new();
+    for (i, c) in ident
+        .to_string()
+        .trim_start_matches("r#")
+        .chars()
+        .enumerate()
+    {
+        if c.is_uppercase() {
+            if i > 0 {
+                name.push('-');
+            }
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Last segment name of type path
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Argument of generic type like `Option<T>` with last segment _name_
+fn generic_argument<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
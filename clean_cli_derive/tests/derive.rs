@@ -0,0 +1,175 @@
+use assert2::{check, let_assert};
+use clean_cli::*;
+use std::net::Ipv4Addr;
+
+#[derive(CleanArgs, Debug)]
+struct Args {
+    /// Lines count
+    #[clean(alias = "n", default = 10)]
+    lines: i64,
+    #[clean(alias = "f")]
+    follow: bool,
+    #[clean(count, alias = "v")]
+    verbose: i64,
+    #[clean(name = "out")]
+    output_file: Option<String>,
+    #[clean(alias = "I")]
+    include_dir: Vec<String>,
+    #[clean(from_str)]
+    address: Option<Ipv4Addr>,
+    #[clean(value_type = ArgType::choice(["fast", "slow"]), default = "fast")]
+    mode: String,
+}
+
+fn cli() -> Cli<DefaultConfig<Option<Args>>> {
+    Cli::builder()
+        .command(CommandBuilder::with_name("run").args_handler(|args: Args, _| Some(args)))
+        .build()
+}
+
+#[test]
+fn defaults() {
+    let cli = cli();
+    let_assert!(Ok(Some(args)) = cli.exec("run"));
+    check!(args.lines == 10);
+    check!(!args.follow);
+    check!(args.verbose == 0);
+    check!(args.output_file == None);
+    check!(args.include_dir.is_empty());
+    check!(args.address == None);
+    check!(args.mode == "fast");
+}
+
+#[test]
+fn all_set() {
+    let cli = cli();
+    let_assert!(
+        Ok(Some(args)) =
+            cli.exec("run -fvvn 5 --out log.txt -I a -I b --address 127.0.0.1 --mode slow")
+    );
+    check!(args.lines == 5);
+    check!(args.follow);
+    check!(args.verbose == 2);
+    check!(args.output_file.as_deref() == Some("log.txt"));
+    check!(args.include_dir == ["a", "b"]);
+    check!(args.address == Some(Ipv4Addr::LOCALHOST));
+    check!(args.mode == "slow");
+}
+
+#[test]
+fn invalid_value() {
+    let cli = cli();
+    let_assert!(Err(Error::InvalidChoice { .. }) = cli.exec("run --mode medium"));
+    let_assert!(Err(Error::Custom { .. }) = cli.exec("run --address nowhere"));
+}
+
+#[derive(CleanArgs)]
+struct Required {
+    /// Name of the thing
+    name: String,
+    ratio: f64,
+}
+
+#[test]
+fn required_parameters() {
+    let cli = <Cli<DefaultConfig<String>>>::builder()
+        .command(
+            CommandBuilder::with_name("set")
+                .args_handler(|args: Required, _| format!("{}={}", args.name, args.ratio)),
+        )
+        .build();
+
+    let_assert!(Ok(result) = cli.exec("set --name x --ratio 0.5"));
+    check!(result == "x=0.5");
+    let_assert!(Err(Error::MissingRequired { parameter, .. }) = cli.exec("set --name x"));
+    check!(parameter == "ratio");
+}
+
+#[test]
+fn parameter_builders() {
+    check!(Required::parameters().len() == 2);
+    check!(Args::parameters().len() == 7);
+}
+
+#[derive(CleanCommands, Debug, PartialEq)]
+enum Cmd {
+    /// Show status
+    Status,
+    #[clean(alias = "p")]
+    Push {
+        #[clean(alias = "f")]
+        force: bool,
+    },
+    #[clean(name = "set")]
+    SetValue(Required),
+    #[clean(subcommands)]
+    RemoteBranch(Remote),
+}
+
+#[derive(CleanCommands, Debug, PartialEq)]
+enum Remote {
+    Add {
+        name: String,
+    },
+    #[clean(alias = "rm")]
+    Remove {
+        name: String,
+    },
+}
+
+impl PartialEq for Required {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.ratio == other.ratio
+    }
+}
+
+impl std::fmt::Debug for Required {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.ratio)
+    }
+}
+
+fn enum_cli() -> Cli<DefaultConfig<Option<Cmd>>> {
+    Cli::builder().enum_commands::<Cmd>().build()
+}
+
+#[test]
+fn enum_commands() {
+    let cli = enum_cli();
+    check!(cli.exec("status").unwrap() == Some(Cmd::Status));
+    check!(cli.exec("push").unwrap() == Some(Cmd::Push { force: false }));
+    check!(cli.exec("p -f").unwrap() == Some(Cmd::Push { force: true }));
+    check!(
+        cli.exec("set --name x --ratio 2").unwrap()
+            == Some(Cmd::SetValue(Required {
+                name: "x".to_owned(),
+                ratio: 2.0
+            }))
+    );
+}
+
+#[test]
+fn enum_nested_commands() {
+    let cli = enum_cli();
+    check!(
+        cli.exec("remote-branch add --name origin").unwrap()
+            == Some(Cmd::RemoteBranch(Remote::Add {
+                name: "origin".to_owned()
+            }))
+    );
+    check!(
+        cli.exec("remote-branch rm --name origin").unwrap()
+            == Some(Cmd::RemoteBranch(Remote::Remove {
+                name: "origin".to_owned()
+            }))
+    );
+    let_assert!(Err(Error::NoHandler("remote-branch")) = cli.exec("remote-branch"));
+}
+
+#[test]
+fn enum_commands_errors() {
+    let cli = enum_cli();
+    let_assert!(Err(Error::NotCommand { .. }) = cli.exec("pull"));
+    let_assert!(Err(Error::MissingRequired { parameter, .. }) = cli.exec("remote-branch add"));
+    check!(parameter == "name");
+}
@@ -0,0 +1,132 @@
+use crate::{
+    command::CommandBuilder,
+    context::{Context, ContextUnit},
+    error::ValueError,
+    parameter::ParameterBuilder,
+    traits::Config,
+};
+
+/// Set of command parameters described by a struct.
+///
+/// Usually it is implemented with `#[derive(CleanArgs)]` from `derive` feature.
+/// Every field becomes a parameter named after the field with `_` replaced by `-`,
+/// and doc comment of the field becomes parameter description.
+///
+/// Field types are mapped to parameters as follows:
+/// - `bool` is a flag, `false` when not set;
+/// - `i64`, `f64` and `String` are required parameters of [`ArgType::Int`](crate::ArgType::Int),
+///   [`ArgType::Float`](crate::ArgType::Float) and [`ArgType::String`](crate::ArgType::String);
+/// - `Option<T>` is an optional parameter of type `T`;
+/// - `Vec<T>` is a [`multiple`](ParameterBuilder::multiple) parameter of type `T`.
+///
+/// Fields accept `#[clean(...)]` attributes:
+/// - `name = "name"` to override parameter name;
+/// - `alias = "a"` to add alias, may be repeated;
+/// - `default = expr` to set default value, so parameter is not required;
+/// - `count` to use [`ArgType::Count`](crate::ArgType::Count) for `i64` field;
+/// - `value_type = expr` to override [`ArgType`](crate::ArgType), e.g. with choice;
+/// - `from_str` to parse field type with [`FromStrParser`](crate::FromStrParser).
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use clean_cli::*;
+///
+/// #[derive(CleanArgs)]
+/// struct Args {
+///     /// Lines count
+///     #[clean(alias = "n", default = 10)]
+///     lines: i64,
+///     #[clean(alias = "f")]
+///     follow: bool,
+/// }
+///
+/// let cli = <Cli<DefaultConfig<(i64, bool)>>>::builder()
+///     .command(CommandBuilder::with_name("tail")
+///         .args_handler(|args: Args, _| (args.lines, args.follow))
+///     )
+///     .build();
+///
+/// assert_eq!(cli.exec("tail -fn 5").unwrap(), (5, true));
+/// assert_eq!(cli.exec("tail").unwrap(), (10, false));
+/// # }
+/// ```
+pub trait CleanArgs: Sized {
+    /// Builders for all parameters
+    fn parameters() -> Vec<ParameterBuilder<'static>>;
+
+    /// Read parameters values from _unit_
+    fn from_context<T: Config>(unit: &ContextUnit<'_, T>) -> Result<Self, ValueError>;
+}
+
+/// Tree of commands described by an enum.
+///
+/// Usually it is implemented with `#[derive(CleanCommands)]` from `derive` feature.
+/// Every variant becomes a command named after the variant in kebab case,
+/// and doc comment of the variant becomes command description.
+///
+/// Variants are mapped to commands as follows:
+/// - unit variant is a command without parameters;
+/// - variant with named fields is a command with parameters described as in [`CleanArgs`];
+/// - variant with single field of [`CleanArgs`] type is a command with parameters of that type;
+/// - variant with single field of [`CleanCommands`] type marked with `#[clean(subcommands)]`
+///   is a command with nested subcommands.
+///
+/// Variants accept `#[clean(name = "name")]` and `#[clean(alias = "a")]` attributes.
+///
+/// Use [`CliBuilder::enum_commands`](crate::CliBuilder::enum_commands) to make
+/// [`Cli::exec`](crate::Cli::exec) return parsed enum instead of invoking handlers.
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use clean_cli::*;
+///
+/// #[derive(CleanCommands, Debug, PartialEq)]
+/// enum Cmd {
+///     /// Show status
+///     Status,
+///     Push {
+///         #[clean(alias = "f")]
+///         force: bool,
+///     },
+///     #[clean(subcommands)]
+///     Remote(Remote),
+/// }
+///
+/// #[derive(CleanCommands, Debug, PartialEq)]
+/// enum Remote {
+///     Add { name: String },
+///     Remove { name: String },
+/// }
+///
+/// let cli = <Cli<DefaultConfig<Option<Cmd>>>>::builder()
+///     .enum_commands::<Cmd>()
+///     .build();
+///
+/// assert_eq!(cli.exec("push -f").unwrap(), Some(Cmd::Push { force: true }));
+/// assert_eq!(
+///     cli.exec("remote add --name origin").unwrap(),
+///     Some(Cmd::Remote(Remote::Add { name: "origin".to_owned() }))
+/// );
+/// # }
+/// ```
+pub trait CleanCommands: Sized {
+    /// Builders for all commands, _handler_ is set to every command without subcommands
+    fn commands<T, F>(handler: &F) -> Vec<CommandBuilder<T>>
+    where
+        T: Config,
+        F: Fn(Context<T>) -> T::Result + Clone + 'static;
+
+    /// Read command from _units_, where the first unit is one of commands of _Self_
+    fn from_units<T: Config>(units: &[ContextUnit<'_, T>]) -> Result<Self, ValueError>;
+
+    /// Read command from all units of _context_ except the root one
+    fn from_context<T: Config>(context: &Context<'_, T>) -> Result<Self, ValueError> {
+        Self::from_units(context.command_units().get(1..).unwrap_or_default())
+    }
+}
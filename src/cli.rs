@@ -1,4 +1,7 @@
-use crate::traits::Config;
+use crate::{
+    args::CleanCommands,
+    traits::{Config, ErrorFormatter, Printer},
+};
 
 use super::command::*;
 use super::context::*;
@@ -6,6 +9,7 @@ use super::error::*;
 use super::parameter::*;
 
 use std::{
+    borrow::Cow,
     collections::{HashMap, VecDeque},
     fmt::Debug,
     rc::Rc,
@@ -37,6 +41,19 @@ pub struct Cli<T: Config> {
     printer: T::Printer,
     need_print_error: bool,
     need_print_help: bool,
+    color: bool,
+    success: Option<SuccessPredicate<T>>,
+}
+
+type Predicate<T> = dyn Fn(&<T as Config>::Result) -> bool;
+
+/// Predicate deciding if result of chained command is successful
+struct SuccessPredicate<T: Config>(Box<Predicate<T>>);
+
+impl<T: Config> std::fmt::Debug for SuccessPredicate<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(stringify!(SuccessPredicate))
+    }
 }
 
 impl<T: Config> Cli<T> {
@@ -47,130 +64,87 @@ impl<T: Config> Cli<T> {
             printer: None,
             need_print_error: Default::default(),
             need_print_help: Default::default(),
+            color: Default::default(),
+            allow_abbreviations: Default::default(),
+            ignore_case: Default::default(),
+            success: None,
         }
     }
 
-    /// Execute _line_
-    pub fn exec<'a>(&'a self, line: &'a str) -> Result<T::Result> {
-        self.exec_line(line).or_else(|e| self.handle_error(e))
+    /// Execute _line_ and return result of the last executed command.
+    /// See [`exec_chain`](Self::exec_chain) for lines with several commands.
+    pub fn exec<'a>(&'a self, line: &'a str) -> Result<'a, T::Result, T::HandlerError> {
+        self.exec_chain(line)
+            .pop()
+            .expect("the first command is always executed")
     }
-    fn exec_line<'a>(&'a self, line: &'a str) -> Result<T::Result> {
-        enum ParseState {
-            ReadFirst,
-            ReadNext,
-            ParametersReaded { params: VecDeque<Rc<Parameter>> },
-        }
 
-        let mut ctx = Context::<T> {
-            units: vec![ContextUnit {
-                command: (self.root.0.as_str(), self.root.1.clone()),
-                parameters: Default::default(),
-                value: None,
-            }],
-            printer: &self.printer,
+    /// Execute commands of _line_ chained with operators and return results
+    /// of all executed commands.
+    ///
+    /// Like in POSIX shell, command after `;` is always executed, after `&&` only
+    /// if the previous executed command succeeded and after `||` only if it failed.
+    /// Operators inside quotes or escaped with backslash are read as text.
+    /// Command succeeds if it returns `Ok` accepted by [`CliBuilder::success_if`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clean_cli::*;
+    ///
+    /// let cli = <Cli<DefaultConfig<bool>>>::builder()
+    ///     .command(CommandBuilder::with_name("check")
+    ///         .use_value(ArgType::Bool)
+    ///         .handler(|ctx| ctx.value_as::<bool>().unwrap())
+    ///     )
+    ///     .success_if(|&result| result)
+    ///     .build();
+    ///
+    /// let results = cli.exec_chain("check false && check true || check true; check false");
+    /// let results: Vec<_> = results.into_iter().map(|r| r.unwrap()).collect();
+    /// assert_eq!(results, [false, true, false]);
+    /// ```
+    pub fn exec_chain<'a>(&'a self, line: &'a str) -> Vec<Result<'a, T::Result, T::HandlerError>> {
+        let chain = match split_chain(line).and_then(check_chain) {
+            Ok(chain) => chain,
+            Err(e) => return vec![self.handle_error(e)],
         };
-        let mut state = ParseState::ReadFirst;
-        let mut pos = 1;
-        let args = split_line(line);
-
-        for (arg, span) in args {
-            match state {
-                ParseState::ReadFirst => {
-                    if arg.starts_with("--") || arg.starts_with('-') {
-                        return Err(Error::CommandExpected(span));
-                    } else if let Some(cmd) = self.commands().get(arg) {
-                        ctx.units.push(ContextUnit {
-                            command: (arg, cmd.clone()),
-                            parameters: Default::default(),
-                            value: None,
-                        });
-                        state = ParseState::ReadNext;
-                    } else {
-                        return Err(Error::NotCommand(span));
-                    }
-                }
 
-                ParseState::ReadNext => {
-                    let last_unit = &mut ctx.units[pos];
-                    let cmd = last_unit.command.1.clone();
-                    let mut new_state: Option<ParseState> = None;
-
-                    if let Some(arg) = arg.strip_prefix("--") {
-                        if let Some(p) = cmd.parameters.get(arg) {
-                            if let ArgType::Bool = p.value_type {
-                                if let Some(p) = last_unit.command.1.parameters.get(arg) {
-                                    last_unit
-                                        .parameters
-                                        .insert(p.name.clone(), (p.clone(), ArgValue::Bool(true)));
-                                }
-                            } else {
-                                let mut params = VecDeque::with_capacity(1);
-                                params.push_back(p.clone());
-                                new_state = Some(ParseState::ParametersReaded { params });
-                            }
-                        } else {
-                            return Err(Error::NotParameter(span));
-                        }
-                    } else if let Some(arg) = arg.strip_prefix('-') {
-                        let mut params = VecDeque::with_capacity(arg.len());
-                        for a in arg.chars() {
-                            let s = a.to_string();
-                            if let Some(p) = cmd.parameters.get(&s) {
-                                if let ArgType::Bool = p.value_type {
-                                    if let Some(p) = last_unit.command.1.parameters.get(&s) {
-                                        last_unit.parameters.insert(
-                                            p.name.clone(),
-                                            (p.clone(), ArgValue::Bool(true)),
-                                        );
-                                    }
-                                } else {
-                                    params.push_back(p.clone());
-                                }
-                            } else {
-                                return Err(Error::NotParameter(span));
-                            }
-                        }
-
-                        if !params.is_empty() {
-                            new_state = Some(ParseState::ParametersReaded { params });
-                        }
-                    } else if let Some(sub) = cmd.subcommands.get(arg) {
-                        ctx.units.push(ContextUnit {
-                            command: (arg, sub.clone()),
-                            parameters: Default::default(),
-                            value: None,
-                        });
-                        pos += 1;
-                        new_state = Some(ParseState::ReadNext);
-                    } else if let Some(v) = cmd.value.as_ref() {
-                        let value = parse_arg(v.clone(), arg, span)?;
-                        last_unit.value = Some(value);
-                    } else {
-                        return Err(Error::NotCommand(span));
-                    }
-
-                    if let Some(s) = new_state {
-                        state = s;
-                    }
-                }
-
-                ParseState::ParametersReaded { mut params } => {
-                    let last_unit = &mut ctx.units[pos];
+        let mut results = Vec::with_capacity(chain.len());
+        let mut success = true;
+        for Chained { operator, words } in chain {
+            let run = match operator {
+                None | Some((Operator::Then, _)) => true,
+                Some((Operator::And, _)) => success,
+                Some((Operator::Or, _)) => !success,
+            };
+            if !run || (words.is_empty() && operator.is_some()) {
+                continue;
+            }
 
-                    let param = params.pop_front().unwrap();
-                    let value = parse_arg(param.value_type.clone(), arg, span)?;
+            let result = self
+                .exec_words(line, words)
+                .or_else(|e| self.handle_error(e));
+            success = match (&result, &self.success) {
+                (Ok(result), Some(predicate)) => predicate.0(result),
+                (Ok(_), None) => true,
+                (Err(_), _) => false,
+            };
+            results.push(result);
+        }
+        results
+    }
 
-                    last_unit
-                        .parameters
-                        .insert(param.name.clone(), (param.clone(), value));
-                    if params.is_empty() {
-                        state = ParseState::ReadNext;
-                    } else {
-                        state = ParseState::ParametersReaded { params };
-                    }
-                }
-            }
+    fn exec_words<'a>(
+        &'a self,
+        line: &'a str,
+        words: Words<'a>,
+    ) -> Result<'a, T::Result, T::HandlerError> {
+        let mut parser = Parser::new(self, line);
+        for (arg, span) in words {
+            parser.read(arg.as_ref(), span)?;
         }
+        let Parser { mut ctx, state, .. } = parser;
 
         if let ParseState::ParametersReaded { mut params } = state {
             if params.len() > 1 {
@@ -178,7 +152,7 @@ impl<T: Config> Cli<T> {
             }
             let param = params.pop_back().unwrap();
             match param.value_type {
-                ArgType::Bool => {}
+                ArgType::Bool | ArgType::Count => {}
                 _ => {
                     return Err(
                         Error::ParameterValueMissed,
@@ -188,12 +162,54 @@ impl<T: Config> Cli<T> {
             }
         };
 
+        // Parent commands are only a path to the dispatched one,
+        // so their required values and parameters are not checked
+        let dispatched = ctx.units.len() - 1;
+        for (i, unit) in ctx.units.iter_mut().enumerate() {
+            let positionals = &unit.command.1.positionals;
+            let missed = positionals.get(unit.values.len()).filter(|p| p.required);
+            if let Some(p) = missed.filter(|_| i == dispatched) {
+                return Err(Error::MissingValue {
+                    command: unit.command.0.to_owned(),
+                    value: p.name.clone(),
+                });
+            }
+
+            let command = unit.command.1.clone();
+            for (key, p) in command.parameters.iter() {
+                if *key != p.name {
+                    continue;
+                }
+                let occurrences = unit.occurrences.get(key).copied().unwrap_or_default();
+                if i == dispatched && occurrences < p.min_occurrences {
+                    return Err(Error::TooFewOccurrences {
+                        span: unit.span,
+                        parameter: p.name.clone(),
+                        min: p.min_occurrences,
+                    });
+                }
+                if unit.parameters.contains_key(key) {
+                    continue;
+                }
+                if i == dispatched && p.required {
+                    return Err(Error::MissingRequired {
+                        command: unit.command.0.to_owned(),
+                        parameter: p.name.clone(),
+                    });
+                }
+                if let Some(default) = p.default.as_ref() {
+                    unit.parameters
+                        .insert(p.name.clone(), (p.clone(), default.clone()));
+                }
+            }
+        }
+
         if let Some(cmd) = ctx.units.last() {
             let name = cmd.command.0;
             let cmd = cmd.command.1.clone();
 
             return match &cmd.exec {
-                Some(f) => Ok(f.borrow_mut()(ctx)),
+                Some(f) => f.borrow_mut()(ctx).map_err(Error::Handler),
                 None => Err(Error::NoHandler(name)),
             };
         }
@@ -201,7 +217,10 @@ impl<T: Config> Cli<T> {
         Ok(Default::default())
     }
 
-    fn handle_error<'a>(&'a self, error: Error<'a>) -> Result<'a, T::Result> {
+    fn handle_error<'a>(
+        &'a self,
+        error: Error<'a, T::HandlerError>,
+    ) -> Result<'a, T::Result, T::HandlerError> {
         if self.need_print_error {
             self.print_error(&error);
         }
@@ -213,8 +232,9 @@ impl<T: Config> Cli<T> {
         Err(error)
     }
 
-    fn print_error(&self, error: &crate::error::Error) {
-        println!("{}", error)
+    fn print_error(&self, error: &Error<T::HandlerError>) {
+        self.printer
+            .print(T::ErrorFormatter::format(error, self.color))
     }
 
     pub(crate) fn print_help(buffer: &str) {
@@ -226,6 +246,191 @@ impl<T: Config> Cli<T> {
     }
 }
 
+pub(crate) enum ParseState {
+    ReadFirst,
+    ReadNext,
+    ParametersReaded { params: VecDeque<Rc<Parameter>> },
+    OptionsEnded,
+}
+
+/// Parser of a line word by word
+pub(crate) struct Parser<'a, T: Config> {
+    root: &'a Command<T>,
+    pub(crate) ctx: Context<'a, T>,
+    pub(crate) state: ParseState,
+    /// Index of the last unit
+    pos: usize,
+    /// Command of the last unit
+    pub(crate) current: &'a Command<T>,
+}
+
+impl<'a, T: Config> Parser<'a, T> {
+    pub(crate) fn new(cli: &'a Cli<T>, line: &'a str) -> Self {
+        Self {
+            root: &cli.root.1,
+            ctx: Context::<T> {
+                units: vec![ContextUnit::new(
+                    (cli.root.0.as_str(), cli.root.1.clone()),
+                    Span {
+                        source: line,
+                        begin: 0,
+                        end: 0,
+                    },
+                )],
+                printer: &cli.printer,
+            },
+            state: ParseState::ReadFirst,
+            pos: 1,
+            current: &cli.root.1,
+        }
+    }
+
+    /// Read next word _arg_ of the line
+    pub(crate) fn read(&mut self, arg: &str, span: Span<'a>) -> Result<'a, (), T::HandlerError> {
+        match std::mem::replace(&mut self.state, ParseState::ReadNext) {
+            ParseState::ReadFirst => {
+                if arg.starts_with("--") || arg.starts_with('-') {
+                    return Err(Error::CommandExpected(span));
+                } else if let Some((name, cmd)) = self
+                    .root
+                    .find_subcommand(arg, true)
+                    .map_err(|candidates| Error::Ambiguous { span, candidates })?
+                {
+                    self.current = cmd;
+                    self.ctx
+                        .units
+                        .push(ContextUnit::new((name, cmd.clone()), span));
+                    self.state = ParseState::ReadNext;
+                } else {
+                    return Err(Error::NotCommand {
+                        span,
+                        suggestions: command_suggestions(self.root, arg),
+                    });
+                }
+            }
+
+            ParseState::ReadNext => {
+                let last_unit = &mut self.ctx.units[self.pos];
+                let cmd = self.current;
+                let mut new_state: Option<ParseState> = None;
+                let next_positional = cmd.positional(last_unit.values.len());
+                let is_negative_value = match next_positional {
+                    Some(p) => {
+                        is_negative_number(arg, &p.value_type) && !has_short_parameter(cmd, arg)
+                    }
+                    None => false,
+                };
+
+                if arg == "--" {
+                    new_state = Some(ParseState::OptionsEnded);
+                } else if let Some(arg) = arg.strip_prefix("--") {
+                    let (arg, inline) = split_assignment(arg, span, 2);
+                    let found =
+                        cmd.find_parameter(arg.0)
+                            .map_err(|candidates| Error::Ambiguous {
+                                span: arg.1,
+                                candidates,
+                            })?;
+                    if let Some((_, p)) = found {
+                        if let Some((value, value_span)) = inline {
+                            read_parameter(last_unit, p, Some(value), value_span)?;
+                        } else if p.value_type.is_flag() {
+                            read_parameter(last_unit, p, None, span)?;
+                        } else {
+                            let mut params = VecDeque::with_capacity(1);
+                            params.push_back(p.clone());
+                            new_state = Some(ParseState::ParametersReaded { params });
+                        }
+                    } else if let Some(p) = cmd.find_negated(arg.0) {
+                        if let Some((_, value_span)) = inline {
+                            return Err(Error::NotValue(value_span));
+                        }
+                        read_parameter(last_unit, p, Some("false"), span)?;
+                    } else {
+                        return Err(Error::NotParameter {
+                            span: arg.1,
+                            suggestions: parameter_suggestions(cmd, arg.0),
+                        });
+                    }
+                } else if let Some(arg) = arg.strip_prefix('-').filter(|_| !is_negative_value) {
+                    let (arg, inline) = split_assignment(arg, span, 1);
+                    let mut params = VecDeque::with_capacity(arg.0.len());
+                    let mut chars = arg.0.chars().peekable();
+                    while let Some(a) = chars.next() {
+                        let s = a.to_string();
+                        if let Some(p) = cmd.parameters.get(&s) {
+                            match inline {
+                                Some((value, value_span)) if chars.peek().is_none() => {
+                                    read_parameter(last_unit, p, Some(value), value_span)?;
+                                }
+                                _ if p.value_type.is_flag() => {
+                                    read_parameter(last_unit, p, None, arg.1)?;
+                                }
+                                _ => params.push_back(p.clone()),
+                            }
+                        } else {
+                            return Err(Error::NotParameter {
+                                span: arg.1,
+                                suggestions: parameter_suggestions(cmd, arg.0),
+                            });
+                        }
+                    }
+
+                    if arg.0.is_empty() {
+                        return Err(Error::NotParameter {
+                            span: arg.1,
+                            suggestions: vec![],
+                        });
+                    }
+
+                    if !params.is_empty() {
+                        new_state = Some(ParseState::ParametersReaded { params });
+                    }
+                } else if let Some((name, sub)) = cmd
+                    // Value expected by command is not expanded to subcommand
+                    .find_subcommand(arg, next_positional.is_none())
+                    .map_err(|candidates| Error::Ambiguous { span, candidates })?
+                {
+                    self.current = sub;
+                    self.ctx
+                        .units
+                        .push(ContextUnit::new((name, sub.clone()), span));
+                    self.pos += 1;
+                    new_state = Some(ParseState::ReadNext);
+                } else {
+                    read_value(cmd, last_unit, arg, span)?;
+                }
+
+                if let Some(s) = new_state {
+                    self.state = s;
+                }
+            }
+
+            ParseState::ParametersReaded { mut params } => {
+                let last_unit = &mut self.ctx.units[self.pos];
+
+                let param = params.pop_front().unwrap();
+                if arg.starts_with('-') && !is_negative_number(arg, &param.value_type) {
+                    return Err(Error::NotValue(span));
+                }
+                read_parameter(last_unit, &param, Some(arg), span)?;
+
+                if params.is_empty() {
+                    self.state = ParseState::ReadNext;
+                } else {
+                    self.state = ParseState::ParametersReaded { params };
+                }
+            }
+
+            ParseState::OptionsEnded => {
+                self.state = ParseState::OptionsEnded;
+                read_value(self.current, &mut self.ctx.units[self.pos], arg, span)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// `CliBuilder` is a helper using for build [`Cli`].
 #[derive(Default, Debug)]
 pub struct CliBuilder<T: Config> {
@@ -233,6 +438,10 @@ pub struct CliBuilder<T: Config> {
     printer: Option<T::Printer>,
     need_print_error: bool,
     need_print_help: bool,
+    color: bool,
+    allow_abbreviations: bool,
+    ignore_case: bool,
+    success: Option<SuccessPredicate<T>>,
 }
 
 impl<T: Config> CliBuilder<T> {
@@ -242,12 +451,35 @@ impl<T: Config> CliBuilder<T> {
         self
     }
 
-    /// Switch output error message to stdout.
+    /// Add commands of _E_ with handlers returning parsed _E_,
+    /// so [`Cli::exec`] can be used as a pure parser with dispatch by `match`
+    pub fn enum_commands<E>(self) -> Self
+    where
+        E: CleanCommands,
+        T: Config<Result = Option<E>>,
+    {
+        let handler = |ctx: Context<T>| match E::from_context(&ctx) {
+            Ok(command) => Some(command),
+            Err(e) => panic!("commands do not match enum: {e}"),
+        };
+        E::commands(&handler)
+            .into_iter()
+            .fold(self, |cli, cmd| cli.command(cmd))
+    }
+
+    /// Switch output error message with printer.
+    /// Message is formatted with [`Config::ErrorFormatter`].
     pub fn print_error(mut self, enable: bool) -> Self {
         self.need_print_error = enable;
         self
     }
 
+    /// Switch ANSI colors in printed error messages.
+    pub fn error_color(mut self, enable: bool) -> Self {
+        self.color = enable;
+        self
+    }
+
     /// Switch output help message to stdout.
     pub fn print_help(mut self, enable: bool) -> Self {
         self.need_print_help = enable;
@@ -259,12 +491,43 @@ impl<T: Config> CliBuilder<T> {
         self
     }
 
+    /// Allow to type unique prefix instead of full name of command or long parameter,
+    /// e.g. `sta` for `status` and `--verb` for `--verbose`.
+    /// If prefix matches several names [`Error::Ambiguous`] is returned.
+    /// Subcommands are not abbreviated while command expects positional values.
+    pub fn allow_abbreviations(mut self, enable: bool) -> Self {
+        self.allow_abbreviations = enable;
+        self
+    }
+
+    /// Match names of commands and long parameters ignoring case, e.g. `SHOW` for `show`.
+    /// Short parameters are still case sensitive, so `-v` and `-V` may be different.
+    /// [`ContextUnit::name`] reports the name as it was declared.
+    /// # Panic
+    /// [`build`](Self::build) panics if different names collide when case is ignored.
+    pub fn ignore_case(mut self, enable: bool) -> Self {
+        self.ignore_case = enable;
+        self
+    }
+
+    /// Set predicate deciding if `Ok` result of command is successful for `&&` and `||`
+    /// operators of [`Cli::exec_chain`]. Any `Ok` result is successful by default.
+    pub fn success_if<F: Fn(&T::Result) -> bool + 'static>(mut self, predicate: F) -> Self {
+        self.success = Some(SuccessPredicate(Box::new(predicate)));
+        self
+    }
+
     /// Build and return `Cli` object.
     pub fn build(mut self) -> Cli<T> {
         let mut commands = Default::default();
+        let options = BuildOptions {
+            print_help: self.need_print_help,
+            abbreviations: self.allow_abbreviations,
+            ignore_case: self.ignore_case,
+        };
 
         while let Some(command_builder) = self.commands.pop() {
-            add_command(&mut commands, command_builder, self.need_print_help);
+            add_command(&mut commands, command_builder, options);
         }
 
         if self.need_print_help {
@@ -272,101 +535,426 @@ impl<T: Config> CliBuilder<T> {
                 .handler(crate::command::help_handler::<T>)
                 .description("This help");
 
-            add_command(&mut commands, cb, self.need_print_help);
+            add_command(&mut commands, cb, options);
         }
 
+        let mut root = Command {
+            name: "root".to_owned(),
+            subcommands: commands,
+            ..Default::default()
+        };
+        root.build_indexes(options);
+
         Cli {
-            root: (
-                "root".to_owned(),
-                Rc::new(Command {
-                    subcommands: commands,
-                    ..Default::default()
-                }),
-            ),
+            root: ("root".to_owned(), Rc::new(root)),
             printer: self.printer.unwrap_or_default(),
             need_print_help: self.need_print_help,
             need_print_error: self.need_print_error,
+            color: self.color,
+            success: self.success,
         }
     }
 }
 
-fn split_line(line: &str) -> impl Iterator<Item = (&str, Span)> {
-    enum LineParseState {
-        EndWord,
-        StartWord { start: usize, quote: Option<char> },
+/// Words of command with their [`Span`]s
+pub(crate) type Words<'a> = Vec<(Cow<'a, str>, Span<'a>)>;
+
+/// Operator between chained commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operator {
+    /// `;`
+    Then,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+}
+
+/// Command of chained line with operator before it
+#[derive(Debug)]
+pub(crate) struct Chained<'a> {
+    pub(crate) operator: Option<(Operator, Span<'a>)>,
+    pub(crate) words: Words<'a>,
+}
+
+/// Split _line_ to commands chained with `;`, `&&` and `||`, and commands to words
+/// like POSIX shell does.
+///
+/// Words are separated by unquoted whitespace. Single quotes preserve everything inside,
+/// double quotes allow escaping `"` and `\` with backslash, and outside of quotes backslash
+/// escapes any character. Quoted segments may appear anywhere in a word and adjacent
+/// segments are concatenated, so `name="a b"` gives `name=a b`. Unquoted and unescaped
+/// operators end the word and the command, so `a;b` gives two commands.
+///
+/// Every word is returned with [`Span`] covering its raw text in _line_. The text is
+/// borrowed from _line_ when it contains no quotes and escapes. There is always at least
+/// one command, commands may be empty.
+pub(crate) fn split_chain<E>(line: &str) -> Result<'_, Vec<Chained<'_>>, E> {
+    enum Quote {
+        None,
+        Single(usize),
+        Double(usize),
     }
 
-    let mut state = LineParseState::EndWord;
-    let line_len = line.len();
+    struct Word {
+        start: usize,
+        text: Option<String>,
+    }
 
-    let result = line.char_indices().filter_map(move |(i, c)| {
-        let mut result: Option<(&str, Span)> = None;
-        if !c.is_whitespace() {
-            match state {
-                LineParseState::EndWord => {
-                    let has_quote = c == '\'' || c == '\"';
-                    state = LineParseState::StartWord {
-                        start: if has_quote { i + 1 } else { i },
-                        quote: if has_quote { Some(c) } else { None },
-                    }
+    let mut result = Vec::new();
+    let mut words = Vec::new();
+    let mut operator = None;
+    let mut word: Option<Word> = None;
+    let mut quote = Quote::None;
+    let mut chars = line.char_indices().peekable();
+
+    let finish = |word: Word, end: usize| {
+        let span = Span {
+            source: line,
+            begin: word.start,
+            end,
+        };
+        let text = match word.text {
+            Some(text) => Cow::Owned(text),
+            None => Cow::Borrowed(span.arg()),
+        };
+        (text, span)
+    };
+
+    while let Some((i, c)) = chars.next() {
+        if let Quote::None = quote {
+            let next = match c {
+                ';' => Some((Operator::Then, 1)),
+                '&' if matches!(chars.peek(), Some((_, '&'))) => Some((Operator::And, 2)),
+                '|' if matches!(chars.peek(), Some((_, '|'))) => Some((Operator::Or, 2)),
+                _ => None,
+            };
+            if let Some((next, len)) = next {
+                if len == 2 {
+                    chars.next();
                 }
-                LineParseState::StartWord { start, quote } => {
-                    if let Some(q) = quote {
-                        if q == c {
-                            result = Some((
-                                &line[start..i],
-                                Span {
-                                    source: line,
-                                    begin: start,
-                                    end: i,
-                                },
-                            ));
-                            state = LineParseState::EndWord;
-                        }
-                    }
+                if let Some(w) = word.take() {
+                    words.push(finish(w, i));
                 }
+                result.push(Chained {
+                    operator,
+                    words: std::mem::take(&mut words),
+                });
+                let span = Span {
+                    source: line,
+                    begin: i,
+                    end: i + len,
+                };
+                operator = Some((next, span));
+                continue;
             }
+        }
 
-            if line_len == i + 1 {
-                if let LineParseState::StartWord { start, quote: _ } = state {
-                    result = Some((
-                        &line[start..],
-                        Span {
-                            source: line,
-                            begin: start,
-                            end: line_len,
-                        },
-                    ));
+        let w = match word.as_mut() {
+            Some(w) => w,
+            None if c.is_whitespace() => continue,
+            None => word.insert(Word {
+                start: i,
+                text: None,
+            }),
+        };
+
+        match quote {
+            Quote::None => match c {
+                c if c.is_whitespace() => {
+                    words.push(finish(word.take().unwrap(), i));
+                }
+                '\'' => {
+                    w.text.get_or_insert_with(|| line[w.start..i].to_string());
+                    quote = Quote::Single(i);
+                }
+                '"' => {
+                    w.text.get_or_insert_with(|| line[w.start..i].to_string());
+                    quote = Quote::Double(i);
+                }
+                '\\' => {
+                    let text = w.text.get_or_insert_with(|| line[w.start..i].to_string());
+                    match chars.next() {
+                        Some((_, escaped)) => text.push(escaped),
+                        None => text.push(c),
+                    }
+                }
+                c => {
+                    if let Some(text) = w.text.as_mut() {
+                        text.push(c);
+                    }
+                }
+            },
+            Quote::Single(_) => {
+                let text = w.text.as_mut().unwrap();
+                match c {
+                    '\'' => quote = Quote::None,
+                    c => text.push(c),
                 }
             }
-        } else {
-            match state {
-                LineParseState::EndWord => {}
-                LineParseState::StartWord { start, quote } => {
-                    if quote.is_none() {
-                        result = Some((
-                            &line[start..i],
-                            Span {
-                                source: line,
-                                begin: start,
-                                end: i,
-                            },
-                        ));
-                        state = LineParseState::EndWord;
+            Quote::Double(_) => {
+                let text = w.text.as_mut().unwrap();
+                match c {
+                    '"' => quote = Quote::None,
+                    '\\' if matches!(chars.peek(), Some((_, '"' | '\\'))) => {
+                        text.push(chars.next().unwrap().1);
                     }
+                    c => text.push(c),
                 }
             }
         }
-        result
+    }
+
+    match quote {
+        Quote::Single(begin) | Quote::Double(begin) => Err(Error::UnterminatedQuote(Span {
+            source: line,
+            begin,
+            end: line.len(),
+        })),
+        Quote::None => {
+            if let Some(w) = word {
+                words.push(finish(w, line.len()));
+            }
+            result.push(Chained { operator, words });
+            Ok(result)
+        }
+    }
+}
+
+/// Check that only the last command of _chain_ is empty, and only after `;`.
+/// A single empty command is allowed.
+fn check_chain<E>(chain: Vec<Chained<'_>>) -> Result<'_, Vec<Chained<'_>>, E> {
+    for (i, chained) in chain.iter().enumerate() {
+        if !chained.words.is_empty() {
+            continue;
+        }
+        match (chain.get(i + 1), chained.operator) {
+            (
+                Some(Chained {
+                    operator: Some((_, span)),
+                    ..
+                }),
+                _,
+            ) => return Err(Error::CommandExpected(*span)),
+            (None, Some((Operator::And | Operator::Or, span))) => {
+                return Err(Error::CommandExpected(span))
+            }
+            _ => {}
+        }
+    }
+    Ok(chain)
+}
+
+/// Read positional value of command _cmd_ into _unit_.
+fn read_value<'a, T: Config>(
+    cmd: &Command<T>,
+    unit: &mut ContextUnit<'a, T>,
+    arg: &str,
+    span: Span<'a>,
+) -> Result<'a, (), T::HandlerError> {
+    match cmd.positional(unit.values.len()) {
+        Some(p) => {
+            let value = parse_arg(&p.value_type, arg, span)?;
+            unit.values.push((p.clone(), value));
+            Ok(())
+        }
+        None if cmd.positionals.is_empty() => Err(Error::NotCommand {
+            span,
+            suggestions: command_suggestions(cmd, arg),
+        }),
+        None => Err(Error::UnexpectedValue(span)),
+    }
+}
+
+/// Parse _arg_ as value of parameter _param_ and store it into _unit_.
+/// _arg_ is `None` for flags that are set without value.
+fn read_parameter<'a, T: Config>(
+    unit: &mut ContextUnit<'a, T>,
+    param: &Rc<Parameter>,
+    arg: Option<&str>,
+    span: Span<'a>,
+) -> Result<'a, (), T::HandlerError> {
+    let occurrences = unit.occurrences.entry(param.name.clone()).or_default();
+    *occurrences += 1;
+    if let Some(max) = param.max_occurrences.filter(|max| *occurrences > *max) {
+        return Err(Error::TooManyOccurrences {
+            span,
+            parameter: param.name.clone(),
+            max,
+        });
+    }
+
+    if let ArgType::Count = param.value_type {
+        let counted = match unit.parameters.get(&param.name) {
+            Some((_, ArgValue::Int(count))) => *count,
+            _ => 0,
+        };
+        // inline value is added to occurrences counted so far
+        let count = match arg {
+            Some(arg) => i64::from_str(arg).map_err(|e| Error::ParseInt(span, e))?,
+            None => 1,
+        };
+        unit.parameters.insert(
+            param.name.clone(),
+            (param.clone(), ArgValue::Int(counted.saturating_add(count))),
+        );
+        return Ok(());
+    }
+
+    let value = match (arg, param.delimiter) {
+        (None, _) => ArgValue::Bool(true),
+        (Some(arg), None) => parse_arg(&param.value_type, arg, span)?,
+        (Some(arg), Some(delimiter)) => {
+            let exact = arg == span.arg();
+            let mut begin = span.begin;
+            let mut items = Vec::new();
+            for item in arg.split(delimiter) {
+                let item_span = match exact {
+                    true => Span {
+                        begin,
+                        end: begin + item.len(),
+                        ..span
+                    },
+                    false => span,
+                };
+                begin += item.len() + delimiter.len_utf8();
+                items.push(parse_arg(&param.value_type, item, item_span)?);
+            }
+            ArgValue::List(items)
+        }
+    };
+
+    if !param.is_multiple() {
+        unit.parameters
+            .insert(param.name.clone(), (param.clone(), value));
+        return Ok(());
+    }
+
+    let (_, list) = unit
+        .parameters
+        .entry(param.name.clone())
+        .or_insert_with(|| (param.clone(), ArgValue::List(Vec::new())));
+    if let ArgValue::List(list) = list {
+        match value {
+            ArgValue::List(items) => list.extend(items),
+            value => list.push(value),
+        }
+    }
+    Ok(())
+}
+
+/// Split parameter token `name=value` on the first `=`.
+/// `prefix` is a length of dashes stripped from the token and used to keep `Span`s
+/// pointing to the source line.
+pub(crate) fn split_assignment<'a, 'b>(
+    arg: &'b str,
+    span: Span<'a>,
+    prefix: usize,
+) -> ((&'b str, Span<'a>), Option<(&'b str, Span<'a>)>) {
+    match arg.split_once('=') {
+        Some((name, value)) => {
+            let name_end = match span.arg().find('=') {
+                Some(i) => span.begin + i,
+                None => span.begin + prefix + name.len(),
+            };
+            (
+                (
+                    name,
+                    Span {
+                        end: name_end,
+                        ..span
+                    },
+                ),
+                Some((
+                    value,
+                    Span {
+                        begin: name_end + 1,
+                        ..span
+                    },
+                )),
+            )
+        }
+        None => ((arg, span), None),
+    }
+}
+
+/// Check that _arg_ is a negative number and _arg_type_ is numeric,
+/// so _arg_ should be read as a value instead of a short parameter.
+fn is_negative_number(arg: &str, arg_type: &ArgType) -> bool {
+    let starts_with_digit = arg
+        .strip_prefix('-')
+        .and_then(|a| a.chars().next())
+        .is_some_and(|c| c.is_ascii_digit() || c == '.');
+
+    starts_with_digit
+        && matches!(arg_type, ArgType::Int | ArgType::Float)
+        && f64::from_str(arg).is_ok()
+}
+
+/// Check that first character of short parameters cluster _arg_ is a known parameter.
+fn has_short_parameter<T: Config>(cmd: &Command<T>, arg: &str) -> bool {
+    arg.strip_prefix('-')
+        .and_then(|a| a.chars().next())
+        .is_some_and(|c| cmd.parameters.contains_key(c.to_string().as_str()))
+}
+
+/// Names of subcommands of _cmd_ similar to _arg_, including aliases
+fn command_suggestions<T: Config>(cmd: &Command<T>, arg: &str) -> Vec<String> {
+    similar(arg, cmd.subcommands.keys().cloned())
+}
+
+/// Parameters of _cmd_ similar to _arg_ in the form they are typed, e.g. `--name`.
+/// _arg_ is a parameter name without dashes.
+fn parameter_suggestions<T: Config>(cmd: &Command<T>, arg: &str) -> Vec<String> {
+    let names = cmd.parameters.iter().flat_map(|(key, p)| {
+        let negated = p.negatable.then(|| format!("no-{}", p.name));
+        std::iter::once(key.clone()).chain(negated)
     });
-    result
+    similar(arg, names)
+        .into_iter()
+        .map(|name| match name.len() {
+            1 => format!("-{name}"),
+            _ => format!("--{name}"),
+        })
+        .collect()
 }
 
-fn parse_arg<'a>(arg_type: ArgType, arg: &'a str, span: Span<'a>) -> Result<'a, ArgValue> {
-    if arg.starts_with('-') && !(arg_type != ArgType::Int || arg_type != ArgType::Float) {
-        return Err(Error::NotValue(span));
+/// _names_ close enough to _arg_ by edit distance, the closest first
+fn similar(arg: &str, names: impl Iterator<Item = String>) -> Vec<String> {
+    let max_distance = (arg.chars().count() / 3).max(1);
+    let mut similar: Vec<_> = names
+        .map(|name| (edit_distance(arg, &name), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    similar.sort();
+    similar.dedup();
+    similar.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Levenshtein distance between _a_ and _b_ counted in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = match ca == *cb {
+                true => prev,
+                false => 1 + prev.min(row[j]).min(row[j + 1]),
+            };
+            prev = current;
+        }
     }
+    row[b.len()]
+}
 
+pub(crate) fn parse_arg<'a, E>(
+    arg_type: &ArgType,
+    arg: &str,
+    span: Span<'a>,
+) -> Result<'a, ArgValue, E> {
     let value: ArgValue = match arg_type {
         ArgType::Bool => match arg {
             "true" | "yes" | "1" | "on" => ArgValue::Bool(true),
@@ -374,7 +962,7 @@ fn parse_arg<'a>(arg_type: ArgType, arg: &'a str, span: Span<'a>) -> Result<'a,
             _ => return Err(Error::ParseBool(span)),
         },
 
-        ArgType::Int => match i64::from_str(arg) {
+        ArgType::Int | ArgType::Count => match i64::from_str(arg) {
             Ok(i) => ArgValue::Int(i),
             Err(e) => return Err(Error::ParseInt(span, e)),
         },
@@ -385,6 +973,34 @@ fn parse_arg<'a>(arg_type: ArgType, arg: &'a str, span: Span<'a>) -> Result<'a,
         },
 
         ArgType::String => ArgValue::String(arg.to_string()),
+
+        ArgType::Choice(choices) => match choices.iter().find(|c| *c == arg) {
+            Some(c) => ArgValue::String(c.clone()),
+            None => {
+                return Err(Error::InvalidChoice {
+                    span,
+                    allowed: choices.clone(),
+                })
+            }
+        },
+
+        ArgType::ChoiceIgnoreCase(choices) => {
+            let arg = arg.to_lowercase();
+            match choices.iter().find(|c| c.to_lowercase() == arg) {
+                Some(c) => ArgValue::String(c.clone()),
+                None => {
+                    return Err(Error::InvalidChoice {
+                        span,
+                        allowed: choices.clone(),
+                    })
+                }
+            }
+        }
+
+        ArgType::Custom(parser) => match parser.parse(arg) {
+            Ok(v) => ArgValue::Custom(v),
+            Err(message) => return Err(Error::Custom { span, message }),
+        },
     };
 
     Ok(value)
@@ -392,78 +1008,190 @@ fn parse_arg<'a>(arg_type: ArgType, arg: &'a str, span: Span<'a>) -> Result<'a,
 
 #[cfg(test)]
 mod test {
-    use super::split_line;
-    use crate::{ArgType, ArgValue};
+    use crate::{
+        error::{Error, Result, Span},
+        ArgType, ArgValue,
+    };
     use assert2::{check, let_assert};
+    use std::borrow::Cow;
+
+    fn split_line(line: &str) -> Result<'_, Vec<(Cow<'_, str>, Span<'_>)>> {
+        let mut chain = super::split_chain(line)?;
+        check!(chain.len() == 1);
+        Ok(chain.remove(0).words)
+    }
+
+    fn parse_arg<'a>(arg_type: &ArgType, arg: &str, span: Span<'a>) -> Result<'a, ArgValue> {
+        super::parse_arg(arg_type, arg, span)
+    }
 
     macro_rules! check_arg {
         ($record:expr, $etalon:literal) => {
+            let (arg, _) = &$record;
+            check!(*arg == $etalon);
+        };
+        ($record:expr, $etalon:literal, $raw:literal) => {
             let (arg, span) = &$record;
-            check!(*arg == span.arg());
             check!(*arg == $etalon);
+            check!(span.arg() == $raw);
         };
     }
 
+    #[test]
+    fn edit_distance() {
+        check!(super::edit_distance("", "") == 0);
+        check!(super::edit_distance("abc", "") == 3);
+        check!(super::edit_distance("", "abc") == 3);
+        check!(super::edit_distance("status", "status") == 0);
+        check!(super::edit_distance("stauts", "status") == 2);
+        check!(super::edit_distance("kitten", "sitting") == 3);
+        check!(super::edit_distance("пример", "примеры") == 1);
+    }
+
     #[test]
     fn split_line_simple() {
         let line = "one two three";
-        let v = super::split_line(line).collect::<Vec<_>>();
-        check_arg!(v[0], "one");
-        check_arg!(v[1], "two");
-        check_arg!(v[2], "three");
+        let v = split_line(line).unwrap();
+        check_arg!(v[0], "one", "one");
+        check_arg!(v[1], "two", "two");
+        check_arg!(v[2], "three", "three");
+        check!(v.iter().all(|(arg, _)| matches!(arg, Cow::Borrowed(_))));
+
+        let line = "  one   two\tthree  ";
+        let v = split_line(line).unwrap();
+        check!(v.len() == 3);
+        check_arg!(v[0], "one", "one");
+        check_arg!(v[1], "two", "two");
+        check_arg!(v[2], "three", "three");
     }
 
     #[test]
     fn split_line_with_quotes() {
         let line = "one \"two\" three";
-        let v = super::split_line(line).collect::<Vec<_>>();
+        let v = split_line(line).unwrap();
         check_arg!(v[0], "one");
-        check_arg!(v[1], "two");
+        check_arg!(v[1], "two", "\"two\"");
         check_arg!(v[2], "three");
 
         let line = "one \"two; two and half\" three";
-        let v = super::split_line(line).collect::<Vec<_>>();
+        let v = split_line(line).unwrap();
         check_arg!(v[0], "one");
         check_arg!(v[1], "two; two and half");
         check_arg!(v[2], "three");
 
         let line = "one two \"three\"";
-        let v = super::split_line(line).collect::<Vec<_>>();
+        let v = split_line(line).unwrap();
         check_arg!(v[0], "one");
         check_arg!(v[1], "two");
         check_arg!(v[2], "three");
+
+        let line = "one 'two \"2\"' \"\"";
+        let v = split_line(line).unwrap();
+        check_arg!(v[0], "one");
+        check_arg!(v[1], "two \"2\"");
+        check_arg!(v[2], "", "\"\"");
+    }
+
+    #[test]
+    fn split_line_with_quotes_inside_word() {
+        let line = "name=\"a b\" --param='x y'z";
+        let v = split_line(line).unwrap();
+        check_arg!(v[0], "name=a b", "name=\"a b\"");
+        check_arg!(v[1], "--param=x yz", "--param='x y'z");
+
+        let line = "'one'\"two\"three";
+        let v = split_line(line).unwrap();
+        check!(v.len() == 1);
+        check_arg!(v[0], "onetwothree", "'one'\"two\"three");
+    }
+
+    #[test]
+    fn split_line_with_escapes() {
+        let line = r#"one\ two \"three\" four\\ "five \"5\" \\ \n" 'six\'"#;
+        let v = split_line(line).unwrap();
+        check_arg!(v[0], "one two", r"one\ two");
+        check_arg!(v[1], "\"three\"", r#"\"three\""#);
+        check_arg!(v[2], r"four\", r"four\\");
+        check_arg!(v[3], r#"five "5" \ \n"#);
+        check_arg!(v[4], r"six\");
+
+        let line = r"trailing\";
+        let v = split_line(line).unwrap();
+        check_arg!(v[0], r"trailing\");
+    }
+
+    #[test]
+    fn split_chain() {
+        use super::Operator;
+
+        let line = r#"connect db1 && load "a && b";status||retry 'x;y' a\;b a|b a&b ;"#;
+        let_assert!(Ok(chain) = super::split_chain::<()>(line));
+        let operators: Vec<_> = chain
+            .iter()
+            .map(|c| c.operator.map(|(op, span)| (op, span.arg())))
+            .collect();
+        check!(
+            operators
+                == [
+                    None,
+                    Some((Operator::And, "&&")),
+                    Some((Operator::Then, ";")),
+                    Some((Operator::Or, "||")),
+                    Some((Operator::Then, ";")),
+                ]
+        );
+        let words: Vec<Vec<_>> = chain
+            .iter()
+            .map(|c| c.words.iter().map(|(w, _)| w.as_ref()).collect())
+            .collect();
+        check!(words[0] == ["connect", "db1"]);
+        check!(words[1] == ["load", "a && b"]);
+        check!(words[2] == ["status"]);
+        check!(words[3] == ["retry", "x;y", "a;b", "a|b", "a&b"]);
+        check!(words[4].is_empty());
+        check_arg!(chain[2].words[0], "status", "status");
+
+        let_assert!(Ok(chain) = super::split_chain::<()>(""));
+        check!(chain.len() == 1);
+        check!(chain[0].words.is_empty());
     }
 
     #[test]
     fn split_line_with_bad_quotes() {
         let line = "one two \"three";
-        let v = super::split_line(line).collect::<Vec<_>>();
-        check_arg!(v[0], "one");
-        check_arg!(v[1], "two");
-        check_arg!(v[2], "three");
+        let_assert!(Err(Error::UnterminatedQuote(span)) = split_line(line));
+        check!(span.arg() == "\"three");
+
+        let line = "one 'two\" three";
+        let_assert!(Err(Error::UnterminatedQuote(span)) = split_line(line));
+        check!(span.arg() == "'two\" three");
+
+        let line = r#"one "two\""#;
+        let_assert!(Err(Error::UnterminatedQuote(span)) = split_line(line));
+        check!(span.begin == 4);
     }
 
     #[test]
     fn parse_arg_bool() {
-        let f = |arg, state, span| {
-            let_assert!(Ok(v) = super::parse_arg(ArgType::Bool, arg, span));
+        let f = |arg: &str, state, span| {
+            let_assert!(Ok(v) = parse_arg(&ArgType::Bool, arg, span));
             let_assert!(ArgValue::Bool(v) = v);
             check!(v == state);
         };
 
-        for (arg, span) in split_line("true 1 yes on") {
-            f(arg, true, span);
+        for (arg, span) in split_line("true 1 yes on").unwrap() {
+            f(&arg, true, span);
         }
 
-        for (arg, span) in split_line("false 0 no off") {
-            f(arg, false, span);
+        for (arg, span) in split_line("false 0 no off").unwrap() {
+            f(&arg, false, span);
         }
     }
 
     #[test]
     fn parse_arg_bool_error() {
         let line = "not_a_bool";
-        check!(let Err(_) = super::parse_arg(ArgType::Bool, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
+        check!(let Err(_) = parse_arg(&ArgType::Bool, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
     }
 
     #[test]
@@ -488,8 +1216,8 @@ mod test {
             numbers.push(num);
         }
 
-        for ((arg, span), state) in split_line(&line).zip(numbers.into_iter()) {
-            let_assert!(Ok(arg_value) = super::parse_arg(ArgType::Int, arg, span));
+        for ((arg, span), state) in split_line(&line).unwrap().into_iter().zip(numbers) {
+            let_assert!(Ok(arg_value) = parse_arg(&ArgType::Int, &arg, span));
             let_assert!(ArgValue::Int(v) = arg_value);
             assert_eq!(v, state)
         }
@@ -498,13 +1226,13 @@ mod test {
     #[test]
     fn parse_arg_int_error() {
         let line = "not_int";
-        check!(let Err(_) = super::parse_arg(ArgType::Int, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
+        check!(let Err(_) = parse_arg(&ArgType::Int, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
     }
 
     #[test]
     fn parse_arg_very_big_int_error() {
         let line = "999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999";
-        check!(let Err(_) = super::parse_arg(ArgType::Int, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
+        check!(let Err(_) = parse_arg(&ArgType::Int, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
     }
 
     #[test]
@@ -530,8 +1258,8 @@ mod test {
             numbers.push(num);
         }
 
-        for ((arg, span), state) in split_line(&line).zip(numbers.into_iter()) {
-            let_assert!(Ok(v) = super::parse_arg(ArgType::Float, arg, span));
+        for ((arg, span), state) in split_line(&line).unwrap().into_iter().zip(numbers) {
+            let_assert!(Ok(v) = parse_arg(&ArgType::Float, &arg, span));
             let_assert!(ArgValue::Float(v) = v);
             check!(v == state);
         }
@@ -540,7 +1268,7 @@ mod test {
     #[test]
     fn parse_arg_float_error() {
         let line = "not_float";
-        check!(let Err(_) = super::parse_arg(ArgType::Float, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
+        check!(let Err(_) = parse_arg(&ArgType::Float, line, crate::error::Span { source: line, begin: 0, end: line.len() }));
     }
 
     #[test]
@@ -548,8 +1276,8 @@ mod test {
         let line = f64::MAX.to_string() + "999";
         let line = line.as_str();
         let_assert!(
-            Ok(f) = super::parse_arg(
-                ArgType::Float,
+            Ok(f) = parse_arg(
+                &ArgType::Float,
                 line,
                 crate::error::Span {
                     source: line,
@@ -1,9 +1,20 @@
-use crate::{context::Context, parameter::*, traits::*};
-use std::{borrow::BorrowMut, cell::RefCell, collections::HashMap, rc::Rc};
+use crate::{
+    args::CleanArgs, cli::parse_arg, context::Context, error::Span, parameter::*,
+    prefix_tree::PrefixTree, traits::*,
+};
+use std::{
+    borrow::{BorrowMut, Cow},
+    cell::RefCell,
+    collections::HashMap,
+    convert::Infallible,
+    rc::Rc,
+};
 
 const NO_DESCRIPTION: &str = "";
 
-type CallBack<T> = RefCell<Box<dyn FnMut(Context<T>) -> <T as Config>::Result>>;
+type CallBack<T> = RefCell<
+    Box<dyn FnMut(Context<T>) -> Result<<T as Config>::Result, <T as Config>::HandlerError>>,
+>;
 
 /// `CommandBuilder` is a helper using for build [`Command`].
 #[derive(Default)]
@@ -11,7 +22,7 @@ pub struct CommandBuilder<T: Config> {
     name: String,
     aliases: Vec<String>,
     subcommands: Vec<CommandBuilder<T>>,
-    value: Option<ArgType>,
+    positionals: Vec<Positional>,
     description: Option<String>,
     parameters: HashMap<String, Rc<Parameter>>,
     handler: Option<CallBack<T>>,
@@ -20,13 +31,35 @@ pub struct CommandBuilder<T: Config> {
 /// `Command` stores all associated options, subcommands, values, and handler.
 #[derive(Default)]
 pub struct Command<T: Config> {
+    pub(crate) name: String,
     pub(crate) subcommands: HashMap<String, Rc<Command<T>>>,
-    pub(crate) value: Option<ArgType>,
+    pub(crate) positionals: Vec<Rc<Positional>>,
     pub(crate) description: Option<String>,
     pub(crate) parameters: HashMap<String, Rc<Parameter>>,
     pub(crate) exec: Option<CallBack<T>>,
+    pub(crate) subcommands_index: NameIndex,
+    pub(crate) parameters_index: NameIndex,
 }
 
+/// Options of building [`Command`] tree
+#[derive(Clone, Copy, Default)]
+pub(crate) struct BuildOptions {
+    pub(crate) print_help: bool,
+    pub(crate) abbreviations: bool,
+    pub(crate) ignore_case: bool,
+}
+
+/// Index of names for lookup by unique prefix or ignoring case
+#[derive(Default)]
+pub(crate) struct NameIndex {
+    /// Declared names by their lowercase form if case is ignored
+    folded: Option<HashMap<String, String>>,
+    /// Names, in lowercase if case is ignored, if abbreviations are allowed
+    prefixes: Option<PrefixTree>,
+}
+
+type Found<'c, V> = Result<Option<(&'c String, &'c Rc<V>)>, Vec<String>>;
+
 impl<T: Config> CommandBuilder<T> {
     /// Create command with name.
     pub fn with_name(name: &str) -> CommandBuilder<T> {
@@ -57,17 +90,127 @@ impl<T: Config> CommandBuilder<T> {
     }
 
     /// Set command handler
-    pub fn handler<F>(mut self, f: F) -> Self
+    pub fn handler<F>(self, mut f: F) -> Self
     where
         F: FnMut(Context<T>) -> T::Result + 'static,
+    {
+        self.try_handler(move |ctx| Ok(f(ctx)))
+    }
+
+    /// Set fallible command handler.
+    /// Its error is returned from [`Cli::exec`](crate::Cli::exec) as
+    /// [`Error::Handler`](crate::Error::Handler).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clean_cli::*;
+    ///
+    /// let cli = <Cli<DefaultConfig<i64, String>>>::builder()
+    ///     .command(CommandBuilder::with_name("div")
+    ///         .positional("a", ArgType::Int)
+    ///         .positional("b", ArgType::Int)
+    ///         .try_handler(|ctx| {
+    ///             let a: i64 = ctx.get("a").unwrap();
+    ///             let b: i64 = ctx.get("b").unwrap();
+    ///             a.checked_div(b).ok_or_else(|| "division by zero".to_string())
+    ///         })
+    ///     )
+    ///     .build();
+    ///
+    /// assert_eq!(cli.exec("div 6 3").unwrap(), 2);
+    /// assert_eq!(cli.exec("div 1 0"), Err(Error::Handler("division by zero".to_string())));
+    /// ```
+    pub fn try_handler<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(Context<T>) -> Result<T::Result, T::HandlerError> + 'static,
     {
         self.handler = Some(RefCell::new(Box::new(f)));
         self
     }
 
+    /// Add all parameters of _A_
+    pub fn args<A: CleanArgs>(self) -> Self {
+        A::parameters()
+            .into_iter()
+            .fold(self, |cmd, param| cmd.parameter(param))
+    }
+
+    /// Add all parameters of _A_ and set handler that receives them as struct
+    /// # Panic
+    /// Handler panics if parameters of command do not match _A_
+    pub fn args_handler<A, F>(self, mut f: F) -> Self
+    where
+        A: CleanArgs,
+        F: FnMut(A, Context<T>) -> T::Result + 'static,
+    {
+        self.args::<A>().handler(move |ctx| {
+            let args = match ctx.args::<A>() {
+                Ok(args) => args,
+                Err(e) => panic!("parameters do not match arguments struct: {e}"),
+            };
+            f(args, ctx)
+        })
+    }
+
     /// Set command value type if required.
-    pub fn use_value(mut self, value_type: ArgType) -> Self {
-        self.value = Some(value_type);
+    /// This is a shortcut for optional positional value named `value`.
+    pub fn use_value(self, value_type: ArgType) -> Self {
+        self.optional_positional("value", value_type)
+    }
+
+    /// Add required positional value.
+    /// Positional values are read in order of adding.
+    pub fn positional(self, name: &str, value_type: ArgType) -> Self {
+        self.add_positional(name, value_type, true, false)
+    }
+
+    /// Add optional positional value.
+    /// Optional values must follow all required values.
+    pub fn optional_positional(self, name: &str, value_type: ArgType) -> Self {
+        self.add_positional(name, value_type, false, false)
+    }
+
+    /// Add positional value that collects all remaining values.
+    /// It must be the last positional value of command.
+    pub fn variadic(self, name: &str, value_type: ArgType) -> Self {
+        self.add_positional(name, value_type, false, true)
+    }
+
+    fn add_positional(
+        mut self,
+        name: &str,
+        value_type: ArgType,
+        required: bool,
+        variadic: bool,
+    ) -> Self {
+        if self.positionals.iter().any(|p| p.name == name) {
+            panic!(
+                "positional value \"{name}\" of command \"{}\" already exist",
+                &self.name
+            );
+        }
+        if let Some(last) = self.positionals.last() {
+            if last.variadic {
+                panic!(
+                    "positional value \"{name}\" of command \"{}\" follows variadic value \"{}\"",
+                    &self.name, &last.name
+                );
+            }
+            if required && !last.required {
+                panic!(
+                    "required positional value \"{name}\" of command \"{}\" follows optional value \"{}\"",
+                    &self.name, &last.name
+                );
+            }
+        }
+
+        self.positionals.push(Positional {
+            name: name.to_owned(),
+            value_type,
+            required,
+            variadic,
+        });
         self
     }
 
@@ -76,8 +219,8 @@ impl<T: Config> CommandBuilder<T> {
         self
     }
 
-    fn build(self, need_print_help: bool) -> (Command<T>, String, Vec<String>) {
-        if self.value.is_none() && self.handler.is_none() && self.subcommands.is_empty() {
+    fn build(self, options: BuildOptions) -> (Command<T>, String, Vec<String>) {
+        if self.positionals.is_empty() && self.handler.is_none() && self.subcommands.is_empty() {
             panic!(
                 "command \"{}: {}\" has no value or handler or subcommand",
                 &self.name,
@@ -85,43 +228,188 @@ impl<T: Config> CommandBuilder<T> {
             )
         }
 
-        (
-            Command::<T> {
-                subcommands: Self::build_subcommands(self.subcommands, need_print_help),
-                value: self.value,
-                description: self.description,
-                parameters: self.parameters,
-                exec: self.handler,
-            },
-            self.name,
-            self.aliases,
-        )
+        let mut command = Command::<T> {
+            name: self.name.clone(),
+            subcommands: Self::build_subcommands(self.subcommands, options),
+            positionals: self.positionals.into_iter().map(Rc::new).collect(),
+            description: self.description,
+            parameters: self.parameters,
+            exec: self.handler,
+            ..Default::default()
+        };
+        command.build_indexes(options);
+
+        (command, self.name, self.aliases)
     }
 
     fn build_subcommands(
         subcommands: Vec<CommandBuilder<T>>,
-        need_print_help: bool,
+        options: BuildOptions,
     ) -> HashMap<String, Rc<Command<T>>> {
         let mut subcommands_builders = subcommands;
         let mut commands = Default::default();
         let sub_count = subcommands_builders.len();
 
         while let Some(command_builder) = subcommands_builders.pop() {
-            add_command(&mut commands, command_builder, need_print_help);
+            add_command(&mut commands, command_builder, options);
         }
 
-        if need_print_help && sub_count > 0 {
+        if options.print_help && sub_count > 0 {
             let cb = <CommandBuilder<T>>::with_name("help")
                 .handler(help_handler::<T>)
                 .description("This help");
 
-            add_command(&mut commands, cb, need_print_help);
+            add_command(&mut commands, cb, options);
         }
 
         commands
     }
 }
 
+impl<T: Config> Command<T> {
+    /// Get positional for value with _index_.
+    /// All values after the last one go to variadic positional.
+    pub(crate) fn positional(&self, index: usize) -> Option<&Rc<Positional>> {
+        match self.positionals.get(index) {
+            Some(p) => Some(p),
+            None => self.positionals.last().filter(|p| p.variadic),
+        }
+    }
+
+    /// Build indexes of subcommands and long parameters names
+    /// # Panic
+    /// Panics if names collide when case is ignored
+    pub(crate) fn build_indexes(&mut self, options: BuildOptions) {
+        self.subcommands_index = NameIndex::new(&self.subcommands, |_| true, options, "command");
+        self.parameters_index = NameIndex::new(
+            &self.parameters,
+            |k| k.chars().count() > 1,
+            options,
+            "parameter",
+        );
+    }
+
+    /// Find subcommand by _name_, ignoring case or by its unique prefix if it is allowed
+    /// and _abbreviated_ is set.
+    /// Returns names of all matched subcommands if prefix is ambiguous.
+    pub(crate) fn find_subcommand(&self, name: &str, abbreviated: bool) -> Found<'_, Command<T>> {
+        self.subcommands_index
+            .find(&self.subcommands, name, abbreviated, |cmd| cmd.name.clone())
+    }
+
+    /// Find long parameter by _name_, ignoring case or by its unique prefix if it is allowed.
+    /// Returns all matched parameters if prefix is ambiguous.
+    pub(crate) fn find_parameter(&self, name: &str) -> Found<'_, Parameter> {
+        self.parameters_index
+            .find(&self.parameters, name, true, |p| format!("--{}", p.name))
+    }
+
+    /// Find negatable parameter by _name_ in `no-<name>` form
+    pub(crate) fn find_negated(&self, name: &str) -> Option<&Rc<Parameter>> {
+        let prefix = name.get(..3)?;
+        let is_negated = match self.parameters_index.ignore_case() {
+            true => prefix.eq_ignore_ascii_case("no-"),
+            false => prefix == "no-",
+        };
+        if !is_negated {
+            return None;
+        }
+        match self.find_parameter(&name[3..]) {
+            Ok(Some((_, p))) if p.negatable => Some(p),
+            _ => None,
+        }
+    }
+}
+
+impl NameIndex {
+    /// Index keys of _map_ accepted by _filter_
+    fn new<V>(
+        map: &HashMap<String, Rc<V>>,
+        filter: impl Fn(&str) -> bool,
+        options: BuildOptions,
+        kind: &str,
+    ) -> Self {
+        let mut index = NameIndex::default();
+        let mut keys: Vec<_> = map.keys().filter(|k| filter(k)).collect();
+        keys.sort();
+
+        if options.ignore_case {
+            let mut folded: HashMap<String, String> = HashMap::new();
+            for key in keys.iter().copied() {
+                match folded.get(&key.to_lowercase()) {
+                    Some(other) if !Rc::ptr_eq(&map[other], &map[key]) => panic!(
+                        "{kind} names \"{other}\" and \"{key}\" collide when case is ignored"
+                    ),
+                    _ => folded.insert(key.to_lowercase(), key.clone()),
+                };
+            }
+            index.folded = Some(folded);
+        }
+
+        if options.abbreviations {
+            index.prefixes = Some(match index.folded.as_ref() {
+                Some(folded) => folded.keys().collect(),
+                None => keys.into_iter().collect(),
+            });
+        }
+
+        index
+    }
+
+    fn find<'c, V>(
+        &self,
+        map: &'c HashMap<String, Rc<V>>,
+        name: &str,
+        abbreviated: bool,
+        display: impl Fn(&V) -> String,
+    ) -> Found<'c, V> {
+        if let Some(found) = map.get_key_value(name) {
+            return Ok(Some(found));
+        }
+
+        let folded = match self.folded.is_some() {
+            true => Cow::Owned(name.to_lowercase()),
+            false => Cow::Borrowed(name),
+        };
+        if let Some(found) = self.declared(&folded).and_then(|k| map.get_key_value(k)) {
+            return Ok(Some(found));
+        }
+
+        // empty name is a prefix of every name, but abbreviates none of them
+        let Some(prefixes) = self
+            .prefixes
+            .as_ref()
+            .filter(|_| abbreviated && !name.is_empty())
+        else {
+            return Ok(None);
+        };
+        let mut found: Vec<(&String, &Rc<V>)> = vec![];
+        for key in prefixes.find(&folded) {
+            let key = self.declared(key).unwrap_or(key);
+            let (key, value) = map.get_key_value(key).expect("index is out of sync");
+            if !found.iter().any(|(_, v)| Rc::ptr_eq(v, value)) {
+                found.push((key, value));
+            }
+        }
+        match found.len() {
+            0 | 1 => Ok(found.pop()),
+            _ => Err(found.into_iter().map(|(_, v)| display(v)).collect()),
+        }
+    }
+
+    pub(crate) fn ignore_case(&self) -> bool {
+        self.folded.is_some()
+    }
+
+    /// Declared name for _folded_ one if case is ignored
+    fn declared(&self, folded: &str) -> Option<&str> {
+        self.folded
+            .as_ref()
+            .and_then(|f| f.get(folded))
+            .map(String::as_str)
+    }
+}
+
 pub(crate) fn format_help<T: Config>(commands: &HashMap<String, Rc<Command<T>>>) -> String {
     let mut buffer = "Help:".to_string();
     commands.iter().for_each(|(key, cmd)| {
@@ -145,7 +433,7 @@ pub(crate) fn help_handler<T: Config>(ctx: Context<T>) -> T::Result {
 pub(crate) fn add_command<T: Config>(
     commands: &mut HashMap<String, Rc<Command<T>>>,
     command_builder: CommandBuilder<T>,
-    need_print_help: bool,
+    options: BuildOptions,
 ) {
     if let Some(exist) = commands.get(&command_builder.name) {
         panic!(
@@ -162,7 +450,7 @@ pub(crate) fn add_command<T: Config>(
         );
     }
 
-    let (command, name, mut aliases) = command_builder.build(need_print_help);
+    let (command, name, mut aliases) = command_builder.build(options);
     let command = Rc::new(command);
     commands.insert(name, command.clone());
     while let Some(alias) = aliases.pop() {
@@ -181,10 +469,56 @@ fn add_parameter(
         );
     }
 
+    if parameter_builder.required && parameter_builder.default.is_some() {
+        panic!(
+            "required parameter \"{}\" can not have default value",
+            &parameter_builder.name
+        );
+    }
+
+    if parameter_builder.negatable && parameter_builder.value_type != ArgType::Bool {
+        panic!(
+            "parameter \"{}\" of type <{}> can not be negatable",
+            &parameter_builder.name, parameter_builder.value_type
+        );
+    }
+
+    if let Some(max) = parameter_builder.max_occurrences {
+        if parameter_builder.min_occurrences > max {
+            panic!(
+                "parameter \"{}\" min occurrences {} is greater than max occurrences {max}",
+                &parameter_builder.name, parameter_builder.min_occurrences
+            );
+        }
+    }
+
+    let multiple = parameter_builder.multiple || parameter_builder.delimiter.is_some();
+    let default = parameter_builder.default.map(|value| {
+        // values of multiple parameter are always a list
+        let value = if multiple && !matches!(value, ArgValue::List(_)) {
+            ArgValue::List(vec![value])
+        } else {
+            value
+        };
+        check_default(
+            &parameter_builder.name,
+            &parameter_builder.value_type,
+            value,
+        )
+    });
+
     let parameter = Rc::new(Parameter {
         name: parameter_builder.name.clone(),
         value_type: parameter_builder.value_type,
         description: parameter_builder.description.unwrap_or("").into(),
+        required: parameter_builder.required,
+        default,
+        multiple: parameter_builder.multiple,
+        delimiter: parameter_builder.delimiter,
+        min_occurrences: parameter_builder.min_occurrences,
+        max_occurrences: parameter_builder.max_occurrences,
+        negatable: parameter_builder.negatable,
+        completer: parameter_builder.completer,
     });
 
     parameters.insert(parameter_builder.name, parameter.clone());
@@ -194,10 +528,44 @@ fn add_parameter(
     }
 }
 
+/// Check default _value_ of parameter _name_ against _value_type_.
+/// Value of other type is parsed from its text, so `"10"` becomes `10` for `<int>`.
+/// # Panic
+/// Panics if value can not be parsed
+fn check_default(name: &str, value_type: &ArgType, value: ArgValue) -> ArgValue {
+    match (value_type, value) {
+        (_, ArgValue::List(items)) => ArgValue::List(
+            items
+                .into_iter()
+                .map(|item| check_default(name, value_type, item))
+                .collect(),
+        ),
+        (ArgType::Bool, value @ ArgValue::Bool(_))
+        | (ArgType::Int | ArgType::Count, value @ ArgValue::Int(_))
+        | (ArgType::Float, value @ ArgValue::Float(_))
+        | (ArgType::String, value @ ArgValue::String(_))
+        | (ArgType::Custom(_), value @ ArgValue::Custom(_)) => value,
+        (_, value) => {
+            let text = value.to_string();
+            let span = Span {
+                source: &text,
+                begin: 0,
+                end: text.len(),
+            };
+            match parse_arg::<Infallible>(value_type, &text, span) {
+                Ok(value) => value,
+                Err(e) => panic!(
+                    "default value \"{text}\" of parameter \"{name}\" is not <{value_type}>: {e}"
+                ),
+            }
+        }
+    }
+}
+
 impl<T: Config> std::fmt::Debug for self::Command<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct(stringify!(Command))
-            .field("value", &self.value)
+            .field("positionals", &self.positionals)
             .field("options", &self.parameters)
             .field("subcommands", &self.subcommands)
             .field("description", &self.description)
@@ -208,7 +576,7 @@ impl<T: Config> std::fmt::Debug for self::Command<T> {
 impl<T: Config> std::fmt::Debug for self::CommandBuilder<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct(stringify!(CommandBuilder))
-            .field("value", &self.value)
+            .field("positionals", &self.positionals)
             .field("options", &self.parameters)
             .field("subcommands", &self.subcommands)
             .field("description", &self.description)
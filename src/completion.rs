@@ -0,0 +1,249 @@
+use crate::{
+    cli::{split_assignment, split_chain, Cli, ParseState, Parser},
+    command::Command,
+    error::Span,
+    parameter::{ArgType, Completer},
+    traits::Config,
+};
+use std::convert::Infallible;
+
+/// Candidates to complete a word of the line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion<'a> {
+    /// Part of the line to be replaced with one of candidates
+    pub span: Span<'a>,
+    /// Candidates in alphabetical order, escaped to be inserted into the line
+    pub candidates: Vec<String>,
+}
+
+impl<T: Config> Cli<T> {
+    /// Complete the word of _line_ that ends at _cursor_ byte offset.
+    ///
+    /// Candidates are subcommands and their aliases at command positions,
+    /// parameters after `-`, choices of typed values and candidates of
+    /// parameter [`Completer`]s. No candidates are returned if the line
+    /// before the word can not be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clean_cli::*;
+    ///
+    /// let cli = <Cli<DefaultConfig<()>>>::builder()
+    ///     .command(CommandBuilder::with_name("status").handler(|_| {}))
+    ///     .command(CommandBuilder::with_name("stash").handler(|_| {}))
+    ///     .build();
+    ///
+    /// let line = "sta";
+    /// let completion = cli.complete(line, line.len());
+    /// assert_eq!(completion.span.arg(), "sta");
+    /// assert_eq!(completion.candidates, ["stash", "status"]);
+    /// ```
+    pub fn complete<'a>(&'a self, line: &'a str, cursor: usize) -> Completion<'a> {
+        let empty = Span {
+            source: line,
+            begin: cursor,
+            end: cursor,
+        };
+        let Some((parser, word)) = self.read_head(line, cursor) else {
+            return Completion {
+                span: empty,
+                candidates: vec![],
+            };
+        };
+
+        let (word, mut span) = word.unwrap_or((String::new(), empty));
+        let mut candidates = candidates(&parser, &word, &mut span);
+        candidates.sort();
+        candidates.dedup();
+        Completion { span, candidates }
+    }
+
+    /// Hint to show after _line_: the rest of the only candidate to complete
+    /// the last word, or the next expected value if line ends with whitespace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clean_cli::*;
+    ///
+    /// let cli = <Cli<DefaultConfig<()>>>::builder()
+    ///     .command(CommandBuilder::with_name("sleep")
+    ///         .positional("seconds", ArgType::Int)
+    ///         .handler(|_| {})
+    ///     )
+    ///     .build();
+    ///
+    /// assert_eq!(cli.hint("sl").as_deref(), Some("eep"));
+    /// assert_eq!(cli.hint("sleep ").as_deref(), Some("<seconds: int>"));
+    /// assert_eq!(cli.hint("sleep 5"), None);
+    /// ```
+    pub fn hint(&self, line: &str) -> Option<String> {
+        let (parser, word) = self.read_head(line, line.len())?;
+        let Some((word, mut span)) = word else {
+            return expected(&parser);
+        };
+        match candidates(&parser, &word, &mut span).as_slice() {
+            [candidate] => candidate
+                .strip_prefix(span.arg())
+                .filter(|rest| !rest.is_empty())
+                .map(str::to_owned),
+            _ => None,
+        }
+    }
+
+    /// Read words of the last chained command of _line_ before _cursor_ except
+    /// the word that ends at cursor, which is returned along with parser. Returns `None` if words can not be read.
+    fn read_head<'a>(
+        &'a self,
+        line: &'a str,
+        cursor: usize,
+    ) -> Option<(Parser<'a, T>, Option<Word<'a>>)> {
+        let head = line.get(..cursor)?;
+        let mut chain = split_chain::<Infallible>(head).ok()?;
+        let mut words = chain.pop()?.words;
+
+        let word = match words.last() {
+            Some((_, span)) if span.end == cursor => words.pop(),
+            _ => None,
+        };
+        let mut parser = Parser::new(self, line);
+        for (arg, span) in words {
+            let span = Span {
+                source: line,
+                ..span
+            };
+            parser.read(arg.as_ref(), span).ok()?;
+        }
+
+        let word = word.map(|(word, span)| {
+            let span = Span {
+                source: line,
+                ..span
+            };
+            (word.into_owned(), span)
+        });
+        Some((parser, word))
+    }
+}
+
+/// Word being completed and its span
+type Word<'a> = (String, Span<'a>);
+
+/// Next value expected in state of _parser_
+fn expected<T: Config>(parser: &Parser<T>) -> Option<String> {
+    let unit = parser.ctx.units.last().expect("root unit always exists");
+    match &parser.state {
+        ParseState::ReadFirst => None,
+        ParseState::ParametersReaded { params } => {
+            params.front().map(|p| format!("<{}>", p.value_type))
+        }
+        ParseState::ReadNext | ParseState::OptionsEnded => parser
+            .current
+            .positional(unit.values.len())
+            .map(|p| p.hint()),
+    }
+}
+
+/// Candidates for _word_ in state of _parser_.
+/// _span_ is narrowed if only a part of word is completed.
+fn candidates<T: Config>(parser: &Parser<T>, word: &str, span: &mut Span) -> Vec<String> {
+    let cmd = parser.current;
+    let unit = parser.ctx.units.last().expect("root unit always exists");
+    let next_positional = || cmd.positional(unit.values.len()).map(|p| &p.value_type);
+
+    match &parser.state {
+        ParseState::ReadFirst => subcommands(cmd, word),
+        ParseState::ParametersReaded { params } => match params.front() {
+            Some(p) => values(&p.value_type, p.completer.as_deref(), word),
+            None => vec![],
+        },
+        ParseState::OptionsEnded => match next_positional() {
+            Some(value_type) => values(value_type, None, word),
+            None => vec![],
+        },
+        ParseState::ReadNext if word.starts_with("--") && word.contains('=') => {
+            let ((name, _), value) = split_assignment(&word[2..], *span, 2);
+            let Some((value, value_span)) = value else {
+                return vec![];
+            };
+            *span = value_span;
+            match cmd.find_parameter(name) {
+                Ok(Some((_, p))) => values(&p.value_type, p.completer.as_deref(), value),
+                _ => vec![],
+            }
+        }
+        ParseState::ReadNext if word.starts_with('-') => parameters(cmd, word),
+        ParseState::ReadNext => {
+            let mut candidates = subcommands(cmd, word);
+            if let Some(value_type) = next_positional() {
+                candidates.extend(values(value_type, None, word));
+            }
+            candidates
+        }
+    }
+}
+
+fn subcommands<T: Config>(cmd: &Command<T>, word: &str) -> Vec<String> {
+    let ignore_case = cmd.subcommands_index.ignore_case();
+    cmd.subcommands
+        .keys()
+        .filter(|key| starts_with(key, word, ignore_case))
+        .map(|key| escape(key))
+        .collect()
+}
+
+fn parameters<T: Config>(cmd: &Command<T>, word: &str) -> Vec<String> {
+    let ignore_case = cmd.parameters_index.ignore_case();
+    cmd.parameters
+        .iter()
+        .flat_map(|(key, p)| {
+            let negated = match key == &p.name && p.negatable {
+                true => Some(format!("--no-{key}")),
+                false => None,
+            };
+            let key = match key.len() {
+                1 => format!("-{key}"),
+                _ => format!("--{key}"),
+            };
+            std::iter::once(key).chain(negated)
+        })
+        .filter(|name| starts_with(name, word, ignore_case && name.len() > 2))
+        .map(|name| escape(&name))
+        .collect()
+}
+
+fn values(value_type: &ArgType, completer: Option<&dyn Completer>, word: &str) -> Vec<String> {
+    if let Some(completer) = completer {
+        return completer.complete(word).iter().map(|c| escape(c)).collect();
+    }
+    let (choices, ignore_case) = match value_type {
+        ArgType::Choice(choices) => (choices, false),
+        ArgType::ChoiceIgnoreCase(choices) => (choices, true),
+        _ => return vec![],
+    };
+    choices
+        .iter()
+        .filter(|c| starts_with(c, word, ignore_case))
+        .map(|c| escape(c))
+        .collect()
+}
+
+fn starts_with(name: &str, prefix: &str, ignore_case: bool) -> bool {
+    match ignore_case {
+        true => name.to_lowercase().starts_with(&prefix.to_lowercase()),
+        false => name.starts_with(prefix),
+    }
+}
+
+/// Escape _text_ with backslashes to be read as a single word
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_whitespace() || matches!(c, '"' | '\'' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
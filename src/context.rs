@@ -1,9 +1,17 @@
-use crate::traits::Config;
+use crate::{
+    args::CleanArgs,
+    error::{InvalidType, Span, ValueError},
+    traits::Config,
+};
 
-use super::{ArgValue, Command, Parameter};
+use super::{ArgValue, Command, Parameter, Positional};
+use std::any::Any;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+type ValueResult<V> = std::result::Result<V, ValueError>;
+
 #[derive(Debug)]
 pub struct Context<'a, T: Config> {
     pub(crate) units: Vec<ContextUnit<'a, T>>,
@@ -18,25 +26,209 @@ impl<'a, T: Config> Context<'a, T> {
     pub fn printer(&self) -> &T::Printer {
         self.printer
     }
+
+    /// Typed value of parameter or positional value of the last command.
+    /// See [`ContextUnit::get`]
+    pub fn get<V>(&self, name: &str) -> ValueResult<V>
+    where
+        V: for<'v> TryFrom<&'v ArgValue, Error = InvalidType>,
+    {
+        self.last_unit(name)?.get(name)
+    }
+
+    /// Typed value of parameter or positional value of the last command or _default_ if it is not set.
+    /// See [`ContextUnit::get_or`]
+    pub fn get_or<V>(&self, name: &str, default: V) -> ValueResult<V>
+    where
+        V: for<'v> TryFrom<&'v ArgValue, Error = InvalidType>,
+    {
+        self.last_unit(name)?.get_or(name, default)
+    }
+
+    /// First positional value of the last command converted to _V_.
+    /// See [`ContextUnit::value_as`]
+    pub fn value_as<V>(&self) -> ValueResult<V>
+    where
+        V: for<'v> TryFrom<&'v ArgValue, Error = InvalidType>,
+    {
+        self.last_unit("value")?.value_as()
+    }
+
+    /// Parameters of the last command read into _A_
+    pub fn args<A: CleanArgs>(&self) -> ValueResult<A> {
+        A::from_context(self.last_unit("args")?)
+    }
+
+    fn last_unit(&self, name: &str) -> ValueResult<&ContextUnit<'a, T>> {
+        self.units
+            .last()
+            .ok_or_else(|| ValueError::Missing(name.to_owned()))
+    }
 }
 
 #[derive(Debug)]
 pub struct ContextUnit<'a, T: Config> {
     pub(crate) command: (&'a str, Rc<Command<T>>),
     pub(crate) parameters: HashMap<String, (Rc<Parameter>, ArgValue)>,
-    pub(crate) value: Option<ArgValue>,
+    pub(crate) values: Vec<(Rc<Positional>, ArgValue)>,
+    pub(crate) occurrences: HashMap<String, usize>,
+    pub(crate) span: Span<'a>,
 }
 
 impl<'a, T: Config> ContextUnit<'a, T> {
+    pub(crate) fn new(command: (&'a str, Rc<Command<T>>), span: Span<'a>) -> Self {
+        Self {
+            command,
+            parameters: Default::default(),
+            values: Default::default(),
+            occurrences: Default::default(),
+            span,
+        }
+    }
+
+    /// Name or alias of the command as it was declared, not as it was typed
     pub fn name(&self) -> &'a str {
         self.command.0
     }
 
+    /// Name the command was declared with, even if it was called by alias
+    pub fn command_name(&self) -> &str {
+        &self.command.1.name
+    }
+
     pub fn parameters(&self) -> &HashMap<String, (Rc<Parameter>, ArgValue)> {
         &self.parameters
     }
 
+    /// First positional value
     pub fn value(&self) -> Option<&ArgValue> {
-        self.value.as_ref()
+        self.values.first().map(|(_, v)| v)
+    }
+
+    /// All positional values in order of appearance
+    pub fn values(&self) -> &Vec<(Rc<Positional>, ArgValue)> {
+        &self.values
+    }
+
+    /// Positional value with _name_.
+    /// For variadic positional the first value is returned.
+    pub fn value_of(&self, name: &str) -> Option<&ArgValue> {
+        self.values
+            .iter()
+            .find(|(p, _)| p.name == name)
+            .map(|(_, v)| v)
+    }
+
+    /// All positional values with _name_
+    pub fn values_of(&self, name: &str) -> Vec<&ArgValue> {
+        self.values
+            .iter()
+            .filter(|(p, _)| p.name == name)
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// Typed value of parameter or positional value with _name_.
+    /// Parameter may be requested by any of its aliases.
+    /// Values of multiple parameters and variadic positionals are read as `Vec<V>`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clean_cli::*;
+    ///
+    /// let cli = <Cli<DefaultConfig<i64>>>::builder()
+    ///     .command(CommandBuilder::with_name("add")
+    ///         .variadic("numbers", ArgType::Int)
+    ///         .parameter(Parameter::with_name("negate").alias("n"))
+    ///         .handler(|ctx| {
+    ///             let sum: i64 = ctx.get_or::<Vec<i64>>("numbers", vec![]).unwrap().iter().sum();
+    ///             match ctx.get_or("negate", false).unwrap() {
+    ///                 true => -sum,
+    ///                 false => sum,
+    ///             }
+    ///         })
+    ///     )
+    ///     .build();
+    ///
+    /// assert_eq!(cli.exec("add 1 2 3").unwrap(), 6);
+    /// assert_eq!(cli.exec("add -n 1 2").unwrap(), -3);
+    /// ```
+    pub fn get<V>(&self, name: &str) -> ValueResult<V>
+    where
+        V: for<'v> TryFrom<&'v ArgValue, Error = InvalidType>,
+    {
+        let (declared, value) = self.lookup(name)?;
+        V::try_from(value.as_ref()).map_err(|e| ValueError::TypeMismatch {
+            name: name.to_owned(),
+            declared,
+            expected: e.expected,
+        })
+    }
+
+    /// Same as [`get`](Self::get) but returns _default_ if value is not set.
+    /// Name not declared by the command is still [`ValueError::Unknown`]
+    pub fn get_or<V>(&self, name: &str, default: V) -> ValueResult<V>
+    where
+        V: for<'v> TryFrom<&'v ArgValue, Error = InvalidType>,
+    {
+        match self.get(name) {
+            Err(ValueError::Missing(_)) => Ok(default),
+            result => result,
+        }
+    }
+
+    /// First positional value converted to _V_
+    pub fn value_as<V>(&self) -> ValueResult<V>
+    where
+        V: for<'v> TryFrom<&'v ArgValue, Error = InvalidType>,
+    {
+        match self.values.first() {
+            Some((p, _)) => self.get(p.name.as_str()),
+            None => Err(ValueError::Missing("value".to_owned())),
+        }
+    }
+
+    /// Reference to value of user-defined type produced by [`ValueParser`](crate::ValueParser)
+    pub fn get_custom<V: Any>(&self, name: &str) -> ValueResult<&V> {
+        let (declared, value) = self.lookup(name)?;
+        let value = match value {
+            Cow::Borrowed(ArgValue::Custom(v)) => v.downcast_ref::<V>(),
+            _ => None,
+        };
+        value.ok_or_else(|| ValueError::TypeMismatch {
+            name: name.to_owned(),
+            declared,
+            expected: std::any::type_name::<V>(),
+        })
+    }
+
+    /// Find value with _name_ and its declared type
+    fn lookup(&self, name: &str) -> ValueResult<(String, Cow<'_, ArgValue>)> {
+        if let Some(p) = self.command.1.parameters.get(name) {
+            let declared = match p.is_multiple() {
+                true => format!("{}...", p.value_type),
+                false => p.value_type.to_string(),
+            };
+            return match self.parameters.get(&p.name) {
+                Some((_, v)) => Ok((declared, Cow::Borrowed(v))),
+                None => Err(ValueError::Missing(name.to_owned())),
+            };
+        }
+
+        let values: Vec<_> = self.values.iter().filter(|(p, _)| p.name == name).collect();
+        match values.first() {
+            Some((p, v)) if !p.variadic => Ok((p.value_type.to_string(), Cow::Borrowed(v))),
+            Some((p, _)) => Ok((
+                format!("{}...", p.value_type),
+                Cow::Owned(ArgValue::List(
+                    values.iter().map(|(_, v)| v.clone()).collect(),
+                )),
+            )),
+            None if self.command.1.positionals.iter().any(|p| p.name == name) => {
+                Err(ValueError::Missing(name.to_owned()))
+            }
+            None => Err(ValueError::Unknown(name.to_owned())),
+        }
     }
 }
@@ -0,0 +1,120 @@
+use crate::error::{Error, Span};
+use std::fmt::Display;
+
+const RED: &str = "\x1b[1;31m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Error prepared for rendering: message, part of the line it refers to,
+/// short label for that part and a hint how to fix it.
+///
+/// # Example
+///
+/// ```rust
+/// use clean_cli::*;
+///
+/// let cli = <Cli<DefaultConfig<()>>>::builder()
+///     .command(CommandBuilder::with_name("sleep").use_value(ArgType::Int))
+///     .build();
+///
+/// let error = cli.exec("sleep 5s").unwrap_err();
+/// assert_eq!(
+///     Diagnostic::new(&error).render(false),
+///     "error: Parse int error: invalid digit found in string\n  \
+///     | sleep 5s\n  \
+///     |       ^^ not an integer\n  \
+///     = hint: expected <int>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic<'a> {
+    pub message: String,
+    pub span: Option<Span<'a>>,
+    pub label: String,
+    pub hint: Option<String>,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub fn new<E: Display>(error: &Error<'a, E>) -> Self {
+        let (label, hint) = match error {
+            Error::NotCommand { suggestions, .. } => ("unknown command", did_you_mean(suggestions)),
+            Error::NotParameter { suggestions, .. } => {
+                ("unknown parameter", did_you_mean(suggestions))
+            }
+            Error::CommandExpected(_) => ("command expected", None),
+            Error::NotValue(_) => ("not a value", None),
+            Error::ParseBool(_) => (
+                "not a boolean",
+                Some("expected <bool>: true, false, yes, no, on, off, 1 or 0".to_owned()),
+            ),
+            Error::ParseInt(..) => ("not an integer", Some("expected <int>".to_owned())),
+            Error::ParseFloat(..) => ("not a number", Some("expected <float>".to_owned())),
+            Error::UnexpectedValue(_) => ("unexpected value", None),
+            Error::TooManyOccurrences { max, .. } => (
+                "set too many times",
+                Some(format!("may be set at most {max} times")),
+            ),
+            Error::TooFewOccurrences { parameter, min, .. } => (
+                "in this command",
+                Some(format!("set \"{parameter}\" at least {min} times")),
+            ),
+            Error::InvalidChoice { allowed, .. } => (
+                "invalid value",
+                Some(format!("expected one of: {}", allowed.join(", "))),
+            ),
+            Error::Custom { .. } => ("invalid value", None),
+            Error::UnterminatedQuote(_) => {
+                ("unterminated quote", Some("close the quote".to_owned()))
+            }
+            Error::Ambiguous { candidates, .. } => (
+                "ambiguous abbreviation",
+                Some(format!("could be one of: '{}'", candidates.join("', '"))),
+            ),
+            Error::ParameterValueMissed
+            | Error::ParserFault
+            | Error::NoHandler(_)
+            | Error::MissingValue { .. }
+            | Error::MissingRequired { .. }
+            | Error::Handler(_) => ("", None),
+        };
+
+        Self {
+            message: error.to_string(),
+            span: error.span(),
+            label: label.to_owned(),
+            hint,
+        }
+    }
+
+    /// Render diagnostic as text, with ANSI colors if _color_ is set
+    pub fn render(&self, color: bool) -> String {
+        let paint = |style: &str, text: &str| match color {
+            true => format!("{style}{text}{RESET}"),
+            false => text.to_owned(),
+        };
+
+        let mut buffer = format!("{}: {}", paint(RED, "error"), paint(BOLD, &self.message));
+        if let Some(span) = self.span {
+            let offset = span.source[..span.begin].chars().count();
+            let width = span.arg().chars().count().max(1);
+            let marker = format!("{} {}", "^".repeat(width), self.label);
+            buffer.push_str(format!("\n  | {}", span.source).as_str());
+            buffer.push_str(
+                format!("\n  | {:offset$}{}", "", paint(RED, marker.trim_end())).as_str(),
+            );
+        }
+        if let Some(hint) = self.hint.as_ref() {
+            buffer.push_str(format!("\n  = {}", paint(CYAN, &format!("hint: {hint}"))).as_str());
+        }
+        buffer
+    }
+}
+
+fn did_you_mean(suggestions: &[String]) -> Option<String> {
+    match suggestions {
+        [] => None,
+        [one] => Some(format!("did you mean '{one}'?")),
+        many => Some(format!("did you mean one of: '{}'?", many.join("', '"))),
+    }
+}
@@ -0,0 +1,599 @@
+use crate::{cli::Cli, completion::Completion, repl::Input, traits::Config};
+use std::io::{self, Read, Write};
+
+const GREY: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// Source of completions and hints for line editing.
+///
+/// It is implemented for [`Cli`] with [`Cli::complete`] and [`Cli::hint`].
+pub trait LineHelper {
+    /// Candidates to complete the word of _line_ that ends at _cursor_ byte offset
+    fn complete<'a>(&'a self, line: &'a str, cursor: usize) -> Completion<'a>;
+    /// Text shown in grey after _line_ when cursor is at its end
+    fn hint(&self, line: &str) -> Option<String>;
+}
+
+impl<T: Config> LineHelper for Cli<T> {
+    fn complete<'a>(&'a self, line: &'a str, cursor: usize) -> Completion<'a> {
+        Cli::complete(self, line, cursor)
+    }
+
+    fn hint(&self, line: &str) -> Option<String> {
+        Cli::hint(self, line)
+    }
+}
+
+/// Terminal line editor for [`Repl`](crate::Repl).
+///
+/// It switches terminal into raw mode while a line is read and supports
+/// Emacs-like key bindings:
+///
+/// | Keys                                | Action                                  |
+/// |-------------------------------------|-----------------------------------------|
+/// | Left, Right, Ctrl-B, Ctrl-F         | move cursor by character                |
+/// | Alt-B, Alt-F, Ctrl-Left, Ctrl-Right | move cursor by word                     |
+/// | Home, End, Ctrl-A, Ctrl-E           | move cursor to start or end of line     |
+/// | Backspace, Delete, Ctrl-D           | delete character                        |
+/// | Ctrl-K, Ctrl-U, Ctrl-W              | kill to end, to start or previous word  |
+/// | Ctrl-Y                              | yank the last killed text               |
+/// | Tab                                 | complete word, cycle through candidates |
+/// | Ctrl-L                              | clear screen                            |
+/// | Ctrl-C                              | discard line                            |
+/// | Ctrl-D on empty line                | end of input                            |
+///
+/// Hints from [`LineHelper`] are shown in grey when cursor is at the end of line.
+/// If stdin is not a terminal or raw mode is not supported on the platform,
+/// lines are read from stdin as is.
+#[derive(Debug, Default)]
+pub struct Editor {
+    /// The last killed text
+    yank: String,
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read line from _input_ key by key, rendering it to _output_ of _width_ columns
+    pub(crate) fn edit(
+        &mut self,
+        input: &mut dyn KeyInput,
+        prompt: &str,
+        output: &mut dyn Write,
+        width: usize,
+        helper: &dyn LineHelper,
+    ) -> io::Result<Option<String>> {
+        let mut line = Line {
+            text: String::new(),
+            cursor: 0,
+            width: width.max(1),
+            row: 0,
+        };
+        let mut cycle: Option<Cycle> = None;
+        line.render(prompt, output, helper, true)?;
+
+        loop {
+            let key = read_key(input)?;
+            if key != Key::Tab {
+                cycle = None;
+            }
+            match key {
+                Key::Eof | Key::Ctrl('d') if line.text.is_empty() => {
+                    output.write_all(b"\r\n")?;
+                    output.flush()?;
+                    return Ok(None);
+                }
+                Key::Eof | Key::Enter => {
+                    line.cursor = line.text.len();
+                    line.render(prompt, output, helper, false)?;
+                    output.write_all(b"\r\n")?;
+                    output.flush()?;
+                    return Ok(Some(line.text));
+                }
+                Key::Ctrl('c') => {
+                    line.cursor = line.text.len();
+                    line.render(prompt, output, helper, false)?;
+                    output.write_all(b"^C\r\n")?;
+                    output.flush()?;
+                    return Ok(Some(String::new()));
+                }
+                Key::Char(c) => {
+                    line.text.insert(line.cursor, c);
+                    line.cursor += c.len_utf8();
+                }
+                Key::Left | Key::Ctrl('b') => line.cursor = line.prev_char(),
+                Key::Right | Key::Ctrl('f') => line.cursor = line.next_char(),
+                Key::WordLeft => line.cursor = line.prev_word(),
+                Key::WordRight => line.cursor = line.next_word(),
+                Key::Home | Key::Ctrl('a') => line.cursor = 0,
+                Key::End | Key::Ctrl('e') => line.cursor = line.text.len(),
+                Key::Backspace => {
+                    let begin = line.prev_char();
+                    line.text.replace_range(begin..line.cursor, "");
+                    line.cursor = begin;
+                }
+                Key::Delete | Key::Ctrl('d') => {
+                    let end = line.next_char();
+                    line.text.replace_range(line.cursor..end, "");
+                }
+                Key::Ctrl('k') => self.yank = line.text.split_off(line.cursor),
+                Key::Ctrl('u') => {
+                    self.yank = line.text.drain(..line.cursor).collect();
+                    line.cursor = 0;
+                }
+                Key::Ctrl('w') => {
+                    let begin = line.prev_word();
+                    self.yank = line.text.drain(begin..line.cursor).collect();
+                    line.cursor = begin;
+                }
+                Key::Ctrl('y') => {
+                    line.text.insert_str(line.cursor, &self.yank);
+                    line.cursor += self.yank.len();
+                }
+                Key::Ctrl('l') => {
+                    output.write_all(b"\x1b[H\x1b[2J")?;
+                    line.row = 0;
+                }
+                Key::Tab => {
+                    if !complete(&mut line, &mut cycle, helper) {
+                        output.write_all(b"\x07")?;
+                    }
+                }
+                _ => continue,
+            }
+            line.render(prompt, output, helper, true)?;
+        }
+    }
+}
+
+impl Input for Editor {
+    fn read_line(
+        &mut self,
+        prompt: &str,
+        output: &mut dyn Write,
+        helper: &dyn LineHelper,
+    ) -> io::Result<Option<String>> {
+        let mut stdin = io::stdin().lock();
+        let Some(raw_mode) = raw::RawMode::enable()? else {
+            return stdin.read_line(prompt, output, helper);
+        };
+        let width = raw_mode.width().unwrap_or(80);
+        let mut terminal = Terminal { stdin, raw_mode };
+        self.edit(&mut terminal, prompt, output, width, helper)
+    }
+}
+
+/// Source of bytes typed by user
+pub(crate) trait KeyInput {
+    /// Next byte, `None` at the end of input
+    fn read_byte(&mut self) -> io::Result<Option<u8>>;
+
+    /// Next byte of escape sequence, `None` if it does not follow in time
+    fn read_escaped(&mut self) -> io::Result<Option<u8>> {
+        self.read_byte()
+    }
+}
+
+impl<R: Read> KeyInput for R {
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        loop {
+            match self.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(byte[0])),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Stdin of terminal in raw mode, original mode is restored on drop
+struct Terminal<'a> {
+    stdin: io::StdinLock<'a>,
+    raw_mode: raw::RawMode,
+}
+
+impl KeyInput for Terminal<'_> {
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        self.stdin.read_byte()
+    }
+
+    /// Lone Esc key is not followed by anything, so bytes of escape sequence
+    /// are awaited only for a short time
+    fn read_escaped(&mut self) -> io::Result<Option<u8>> {
+        self.raw_mode.set_timeout(true)?;
+        let byte = self.stdin.read_byte();
+        self.raw_mode.set_timeout(false)?;
+        byte
+    }
+}
+
+/// Edited line with cursor as byte offset
+struct Line {
+    text: String,
+    cursor: usize,
+    /// Number of terminal columns
+    width: usize,
+    /// Row of cursor counted from the first row of rendered line
+    row: usize,
+}
+
+impl Line {
+    fn prev_char(&self) -> usize {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_char(&self) -> usize {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    /// Start of the word before cursor
+    fn prev_word(&self) -> usize {
+        let head = self.text[..self.cursor].trim_end();
+        head.rfind(char::is_whitespace).map_or(0, |i| {
+            i + head[i..].chars().next().map_or(1, char::len_utf8)
+        })
+    }
+
+    /// End of the word after cursor
+    fn next_word(&self) -> usize {
+        let tail = &self.text[self.cursor..];
+        let start = tail.len() - tail.trim_start().len();
+        tail[start..]
+            .find(char::is_whitespace)
+            .map_or(self.text.len(), |i| self.cursor + start + i)
+    }
+
+    /// Redraw line, with hint if _hint_ is set and cursor is at the end.
+    /// Line longer than terminal width is wrapped to the next rows.
+    fn render(
+        &mut self,
+        prompt: &str,
+        output: &mut dyn Write,
+        helper: &dyn LineHelper,
+        hint: bool,
+    ) -> io::Result<()> {
+        let mut buffer = match self.row {
+            0 => String::new(),
+            row => format!("\x1b[{row}A"),
+        };
+        buffer.push_str(&format!("\r{prompt}{}", self.text));
+        let mut end = prompt.chars().count() + self.text.chars().count();
+        if hint && self.cursor == self.text.len() {
+            if let Some(hint) = helper.hint(&self.text) {
+                end += hint.chars().count();
+                buffer.push_str(&format!("{GREY}{hint}{RESET}"));
+            }
+        }
+        // cursor stays at the end of full row until the next character is written
+        if end > 0 && end.is_multiple_of(self.width) {
+            buffer.push_str("\r\n");
+        }
+        buffer.push_str("\x1b[J");
+
+        let position = prompt.chars().count() + self.text[..self.cursor].chars().count();
+        self.row = position / self.width;
+        let up = end / self.width - self.row;
+        if up > 0 {
+            buffer.push_str(&format!("\x1b[{up}A"));
+        }
+        buffer.push('\r');
+        let column = position % self.width;
+        if column > 0 {
+            buffer.push_str(&format!("\x1b[{column}C"));
+        }
+        output.write_all(buffer.as_bytes())?;
+        output.flush()
+    }
+}
+
+/// State of cycling through completion candidates with Tab
+struct Cycle {
+    /// Start of the completed word
+    begin: usize,
+    /// Word as typed before completion
+    word: String,
+    candidates: Vec<String>,
+    /// Index of inserted candidate, equal to number of candidates for typed word
+    index: usize,
+}
+
+/// Complete word before cursor or replace it with the next candidate.
+/// Returns `false` if there are no candidates.
+fn complete(line: &mut Line, cycle: &mut Option<Cycle>, helper: &dyn LineHelper) -> bool {
+    let state = match cycle {
+        Some(state) => state,
+        None => {
+            let completion = helper.complete(&line.text, line.cursor);
+            let begin = completion.span.begin;
+            let mut candidates = completion.candidates;
+            match candidates.len() {
+                0 => return false,
+                1 => {
+                    let mut candidate = candidates.remove(0);
+                    candidate.push(' ');
+                    line.text.replace_range(begin..line.cursor, &candidate);
+                    line.cursor = begin + candidate.len();
+                    return true;
+                }
+                _ => cycle.insert(Cycle {
+                    begin,
+                    word: line.text[begin..line.cursor].to_owned(),
+                    index: candidates.len(),
+                    candidates,
+                }),
+            }
+        }
+    };
+
+    state.index = (state.index + 1) % (state.candidates.len() + 1);
+    let text = state.candidates.get(state.index).unwrap_or(&state.word);
+    line.text.replace_range(state.begin..line.cursor, text);
+    line.cursor = state.begin + text.len();
+    true
+}
+
+#[derive(Debug, PartialEq)]
+enum Key {
+    Char(char),
+    /// Control character with its lowercase letter
+    Ctrl(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Home,
+    End,
+    Unknown,
+    Eof,
+}
+
+fn read_key(input: &mut dyn KeyInput) -> io::Result<Key> {
+    let Some(byte) = input.read_byte()? else {
+        return Ok(Key::Eof);
+    };
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b => read_escape(input)?,
+        0x01..=0x1a => Key::Ctrl((b'a' + byte - 1) as char),
+        0x00..=0x1f => Key::Unknown,
+        _ => {
+            let mut bytes = vec![byte];
+            let len = match byte {
+                0xf0.. => 4,
+                0xe0.. => 3,
+                0xc0.. => 2,
+                _ => 1,
+            };
+            for _ in 1..len {
+                match input.read_byte()? {
+                    Some(byte) => bytes.push(byte),
+                    None => break,
+                }
+            }
+            match std::str::from_utf8(&bytes)
+                .ok()
+                .and_then(|s| s.chars().next())
+            {
+                Some(c) => Key::Char(c),
+                None => Key::Unknown,
+            }
+        }
+    };
+    Ok(key)
+}
+
+/// Read key sequence after escape character
+fn read_escape(input: &mut dyn KeyInput) -> io::Result<Key> {
+    let key = match input.read_escaped()? {
+        Some(b'b') => Key::WordLeft,
+        Some(b'f') => Key::WordRight,
+        Some(b'[' | b'O') => {
+            let mut params = String::new();
+            loop {
+                match input.read_escaped()? {
+                    Some(byte @ 0x40..=0x7e) => break csi_key(&params, byte),
+                    Some(byte) => params.push(byte as char),
+                    None => break Key::Unknown,
+                }
+            }
+        }
+        _ => Key::Unknown,
+    };
+    Ok(key)
+}
+
+/// Key of control sequence with _params_ and _last_ byte
+fn csi_key(params: &str, last: u8) -> Key {
+    match (params, last) {
+        ("1;5" | "1;3", b'D') => Key::WordLeft,
+        ("1;5" | "1;3", b'C') => Key::WordRight,
+        (_, b'D') => Key::Left,
+        (_, b'C') => Key::Right,
+        (_, b'H') | ("1" | "7", b'~') => Key::Home,
+        (_, b'F') | ("4" | "8", b'~') => Key::End,
+        ("3", b'~') => Key::Delete,
+        _ => Key::Unknown,
+    }
+}
+
+// `Termios` layout and flag values are the generic ones of Linux,
+// other architectures differ in them
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod raw {
+    use std::{io, os::raw::c_ulong};
+
+    const STDIN: i32 = 0;
+    const TCSANOW: i32 = 0;
+    const TCSADRAIN: i32 = 1;
+    const BRKINT: u32 = 0o2;
+    const INPCK: u32 = 0o20;
+    const ISTRIP: u32 = 0o40;
+    const ICRNL: u32 = 0o400;
+    const IXON: u32 = 0o2000;
+    const OPOST: u32 = 0o1;
+    const CS8: u32 = 0o60;
+    const ISIG: u32 = 0o1;
+    const ICANON: u32 = 0o2;
+    const ECHO: u32 = 0o10;
+    const IEXTEN: u32 = 0o100000;
+    const VTIME: usize = 5;
+    const VMIN: usize = 6;
+    const TIOCGWINSZ: c_ulong = 0x5413;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Termios {
+        c_iflag: u32,
+        c_oflag: u32,
+        c_cflag: u32,
+        c_lflag: u32,
+        c_line: u8,
+        c_cc: [u8; 32],
+        c_ispeed: u32,
+        c_ospeed: u32,
+    }
+
+    #[repr(C)]
+    struct Winsize {
+        ws_row: u16,
+        ws_col: u16,
+        ws_xpixel: u16,
+        ws_ypixel: u16,
+    }
+
+    extern "C" {
+        fn isatty(fd: i32) -> i32;
+        fn ioctl(fd: i32, request: c_ulong, ...) -> i32;
+        fn tcgetattr(fd: i32, termios: *mut Termios) -> i32;
+        fn tcsetattr(fd: i32, action: i32, termios: *const Termios) -> i32;
+    }
+
+    /// Raw mode of terminal on stdin, original mode is restored on drop
+    pub(super) struct RawMode {
+        original: Termios,
+        raw: Termios,
+    }
+
+    impl RawMode {
+        /// Switch terminal into raw mode. Returns `None` if stdin is not a terminal.
+        pub(super) fn enable() -> io::Result<Option<Self>> {
+            // SAFETY: `isatty` only checks the file descriptor
+            if unsafe { isatty(STDIN) } != 1 {
+                return Ok(None);
+            }
+            // SAFETY: `Termios` matches `struct termios` of Linux libc
+            // and is fully initialized by successful `tcgetattr`
+            let original = unsafe {
+                let mut termios = std::mem::zeroed::<Termios>();
+                if tcgetattr(STDIN, &mut termios) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                termios
+            };
+
+            let mut raw = original;
+            raw.c_iflag &= !(BRKINT | ICRNL | INPCK | ISTRIP | IXON);
+            raw.c_oflag &= !OPOST;
+            raw.c_cflag |= CS8;
+            raw.c_lflag &= !(ECHO | ICANON | IEXTEN | ISIG);
+            raw.c_cc[VMIN] = 1;
+            raw.c_cc[VTIME] = 0;
+            // SAFETY: `raw` is a valid `Termios` copied from the original one
+            if unsafe { tcsetattr(STDIN, TCSADRAIN, &raw) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Some(Self { original, raw }))
+        }
+
+        /// Make read return nothing if no byte comes in 0.1 second,
+        /// or wait for a byte if _timeout_ is not set
+        pub(super) fn set_timeout(&self, timeout: bool) -> io::Result<()> {
+            let mut raw = self.raw;
+            if timeout {
+                raw.c_cc[VMIN] = 0;
+                raw.c_cc[VTIME] = 1;
+            }
+            // SAFETY: `raw` is a valid `Termios` copied from the original one
+            if unsafe { tcsetattr(STDIN, TCSANOW, &raw) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// Number of terminal columns, `None` if it is unknown
+        pub(super) fn width(&self) -> Option<usize> {
+            // SAFETY: `Winsize` matches `struct winsize` filled by `TIOCGWINSZ`
+            let size = unsafe {
+                let mut size = std::mem::zeroed::<Winsize>();
+                if ioctl(STDIN, TIOCGWINSZ, &mut size as *mut Winsize) != 0 {
+                    return None;
+                }
+                size
+            };
+            Some(size.ws_col as usize).filter(|&width| width > 0)
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            // SAFETY: original mode was returned by `tcgetattr`
+            unsafe { tcsetattr(STDIN, TCSADRAIN, &self.original) };
+        }
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+)))]
+mod raw {
+    use std::io;
+
+    pub(super) struct RawMode;
+
+    impl RawMode {
+        /// Raw mode is not supported, lines are read as is
+        pub(super) fn enable() -> io::Result<Option<Self>> {
+            Ok(None)
+        }
+
+        pub(super) fn set_timeout(&self, _timeout: bool) -> io::Result<()> {
+            Ok(())
+        }
+
+        pub(super) fn width(&self) -> Option<usize> {
+            None
+        }
+    }
+}
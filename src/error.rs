@@ -1,15 +1,25 @@
-use std::fmt::Display;
+use std::{convert::Infallible, fmt::Display, ops::Range, path::PathBuf};
 
 use thiserror::Error as ThisError;
 
-pub type Result<'a, T> = std::result::Result<T, Error<'a>>;
+pub type Result<'a, T, E = Infallible> = std::result::Result<T, Error<'a, E>>;
 
+/// Error of parsing or executing a line.
+/// _E_ is [`Config::HandlerError`](crate::Config::HandlerError) returned by fallible handlers.
 #[derive(ThisError, Debug, Clone, PartialEq)]
-pub enum Error<'a> {
-    #[error("Not a command: {0}")]
-    NotCommand(Span<'a>),
-    #[error("Not valid parameter: {0}")]
-    NotParameter(Span<'a>),
+pub enum Error<'a, E = Infallible> {
+    #[error("Not a command: {span}")]
+    NotCommand {
+        span: Span<'a>,
+        /// Similar commands that may be meant
+        suggestions: Vec<String>,
+    },
+    #[error("Not valid parameter: {span}")]
+    NotParameter {
+        span: Span<'a>,
+        /// Similar parameters that may be meant
+        suggestions: Vec<String>,
+    },
     #[error("Command expected, got: {0}")]
     CommandExpected(Span<'a>),
     #[error("Missed parameter value")]
@@ -30,8 +40,206 @@ pub enum Error<'a> {
     ParseInt(Span<'a>, std::num::ParseIntError),
     #[error("Parse float error: {1}")]
     ParseFloat(Span<'a>, std::num::ParseFloatError),
+    #[error("Unexpected value: {0}")]
+    UnexpectedValue(Span<'a>),
+    #[error("Missed value <{value}> of command: {command}")]
+    MissingValue { command: String, value: String },
+    #[error("Missed required parameter \"{parameter}\" of command: {command}")]
+    MissingRequired { command: String, parameter: String },
+    #[error("Parameter \"{parameter}\" is set more than {max} times: {span}")]
+    TooManyOccurrences {
+        span: Span<'a>,
+        parameter: String,
+        max: usize,
+    },
+    #[error("Parameter \"{parameter}\" must be set at least {min} times")]
+    TooFewOccurrences {
+        span: Span<'a>,
+        parameter: String,
+        min: usize,
+    },
+    #[error("Invalid value: {span}. Possible values: {}", allowed.join(", "))]
+    InvalidChoice {
+        span: Span<'a>,
+        allowed: Vec<String>,
+    },
+    #[error("Invalid value: {span}. {message}")]
+    Custom { span: Span<'a>, message: String },
+    #[error("Unterminated quote: {0}")]
+    UnterminatedQuote(Span<'a>),
+    #[error("Ambiguous abbreviation: {span}. Possible values: {}", candidates.join(", "))]
+    Ambiguous {
+        span: Span<'a>,
+        candidates: Vec<String>,
+    },
+    #[error("Handler error: {0}")]
+    Handler(E),
 }
 
+/// Variant of [`Error`] without its data, kept by [`OwnedError`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    NotCommand,
+    NotParameter,
+    CommandExpected,
+    ParameterValueMissed,
+    ParserFault,
+    NoHandler,
+    NotValue,
+    ParseBool,
+    ParseInt,
+    ParseFloat,
+    UnexpectedValue,
+    MissingValue,
+    MissingRequired,
+    TooManyOccurrences,
+    TooFewOccurrences,
+    InvalidChoice,
+    Custom,
+    UnterminatedQuote,
+    Ambiguous,
+    Handler,
+}
+
+impl<'a, E> Error<'a, E> {
+    /// Variant of error without its data
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::NotCommand { .. } => ErrorKind::NotCommand,
+            Error::NotParameter { .. } => ErrorKind::NotParameter,
+            Error::CommandExpected(_) => ErrorKind::CommandExpected,
+            Error::ParameterValueMissed => ErrorKind::ParameterValueMissed,
+            Error::ParserFault => ErrorKind::ParserFault,
+            Error::NoHandler(_) => ErrorKind::NoHandler,
+            Error::NotValue(_) => ErrorKind::NotValue,
+            Error::ParseBool(_) => ErrorKind::ParseBool,
+            Error::ParseInt(..) => ErrorKind::ParseInt,
+            Error::ParseFloat(..) => ErrorKind::ParseFloat,
+            Error::UnexpectedValue(_) => ErrorKind::UnexpectedValue,
+            Error::MissingValue { .. } => ErrorKind::MissingValue,
+            Error::MissingRequired { .. } => ErrorKind::MissingRequired,
+            Error::TooManyOccurrences { .. } => ErrorKind::TooManyOccurrences,
+            Error::TooFewOccurrences { .. } => ErrorKind::TooFewOccurrences,
+            Error::InvalidChoice { .. } => ErrorKind::InvalidChoice,
+            Error::Custom { .. } => ErrorKind::Custom,
+            Error::UnterminatedQuote(_) => ErrorKind::UnterminatedQuote,
+            Error::Ambiguous { .. } => ErrorKind::Ambiguous,
+            Error::Handler(_) => ErrorKind::Handler,
+        }
+    }
+
+    /// Part of the line the error refers to
+    pub fn span(&self) -> Option<Span<'a>> {
+        match self {
+            Error::NotCommand { span, .. }
+            | Error::NotParameter { span, .. }
+            | Error::CommandExpected(span)
+            | Error::NotValue(span)
+            | Error::ParseBool(span)
+            | Error::ParseInt(span, _)
+            | Error::ParseFloat(span, _)
+            | Error::UnexpectedValue(span)
+            | Error::TooManyOccurrences { span, .. }
+            | Error::TooFewOccurrences { span, .. }
+            | Error::InvalidChoice { span, .. }
+            | Error::Custom { span, .. }
+            | Error::UnterminatedQuote(span)
+            | Error::Ambiguous { span, .. } => Some(*span),
+            Error::ParameterValueMissed
+            | Error::ParserFault
+            | Error::NoHandler(_)
+            | Error::MissingValue { .. }
+            | Error::MissingRequired { .. }
+            | Error::Handler(_) => None,
+        }
+    }
+}
+
+impl<'a, E: Display> Error<'a, E> {
+    /// Detach error from the line, so it can be stored or sent to another thread
+    pub fn into_owned(self) -> OwnedError {
+        OwnedError {
+            kind: self.kind(),
+            message: self.to_string(),
+            location: self
+                .span()
+                .map(|span| (span.source.to_owned(), span.begin..span.end)),
+        }
+    }
+}
+
+/// [`Error`] that owns its message and the line it refers to
+#[derive(ThisError, Debug, Clone, PartialEq, Eq)]
+#[error("{message}")]
+pub struct OwnedError {
+    kind: ErrorKind,
+    message: String,
+    location: Option<(String, Range<usize>)>,
+}
+
+impl OwnedError {
+    /// Variant of the original [`Error`]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Source line if error refers to some part of it
+    pub fn line(&self) -> Option<&str> {
+        self.location.as_ref().map(|(line, _)| line.as_str())
+    }
+
+    /// Byte range of the line the error refers to
+    pub fn range(&self) -> Option<Range<usize>> {
+        self.location.as_ref().map(|(_, range)| range.clone())
+    }
+
+    /// Text of the line the error refers to
+    pub fn arg(&self) -> Option<&str> {
+        self.location
+            .as_ref()
+            .map(|(line, range)| &line[range.clone()])
+    }
+}
+
+impl<'a, E: Display> From<Error<'a, E>> for OwnedError {
+    fn from(error: Error<'a, E>) -> Self {
+        error.into_owned()
+    }
+}
+
+/// Error of script line executed by [`Cli::exec_script`](crate::Cli::exec_script).
+///
+/// It is shown as `file:line:column: message`, or as `line N, column M: message`
+/// if script is not read from file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    /// Script file if it is executed with [`Cli::exec_file`](crate::Cli::exec_file)
+    pub file: Option<PathBuf>,
+    /// Number of the line the error refers to, starting from 1
+    pub line: usize,
+    /// Column of the part of the line the error refers to, starting from 1
+    pub column: Option<usize>,
+    pub error: OwnedError,
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, self.column) {
+            (Some(file), Some(column)) => write!(f, "{}:{}:{column}", file.display(), self.line)?,
+            (Some(file), None) => write!(f, "{}:{}", file.display(), self.line)?,
+            (None, Some(column)) => write!(f, "line {}, column {column}", self.line)?,
+            (None, None) => write!(f, "line {}", self.line)?,
+        }
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Span<'a> {
     pub source: &'a str,
@@ -40,7 +248,7 @@ pub struct Span<'a> {
 }
 
 impl<'a> Span<'a> {
-    pub fn arg(&'a self) -> &'a str {
+    pub fn arg(&self) -> &'a str {
         &self.source[self.begin..self.end]
     }
 }
@@ -50,3 +258,25 @@ impl<'a> Display for Span<'a> {
         self.arg().fmt(f)
     }
 }
+
+/// Error of typed access to values of [`ContextUnit`](crate::ContextUnit)
+#[derive(ThisError, Debug, Clone, PartialEq)]
+pub enum ValueError {
+    #[error("Value \"{0}\" is not set")]
+    Missing(String),
+    #[error("Value \"{0}\" is not declared by command")]
+    Unknown(String),
+    #[error("Value \"{name}\" has type <{declared}>, but <{expected}> requested")]
+    TypeMismatch {
+        name: String,
+        declared: String,
+        expected: &'static str,
+    },
+}
+
+/// Error of converting [`ArgValue`](crate::ArgValue) into Rust type
+#[derive(ThisError, Debug, Clone, PartialEq)]
+#[error("Value can not be converted to <{expected}>")]
+pub struct InvalidType {
+    pub expected: &'static str,
+}
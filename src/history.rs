@@ -0,0 +1,163 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+/// How [`History`] treats repeated lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dedup {
+    /// Keep all lines
+    Off,
+    /// Skip line equal to the previous one
+    #[default]
+    Consecutive,
+    /// Remove earlier copies of line
+    All,
+}
+
+/// Bounded list of executed lines.
+///
+/// Lines are numbered from 1 and keep their numbers when the oldest lines
+/// are dropped or earlier copies are removed by [`Dedup::All`], so `!n` recalls
+/// the same line as shown by `history` command.
+///
+/// # Example
+///
+/// ```rust
+/// use clean_cli::*;
+///
+/// let mut history = History::new(2).ignore_space(true);
+/// history.push("first");
+/// history.push(" secret");
+/// history.push("second");
+/// history.push("third");
+///
+/// assert_eq!(history.get(1), None);
+/// assert_eq!(history.get(2), Some("second"));
+/// assert_eq!(history.last(), Some("third"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct History {
+    /// Lines with their numbers in ascending order
+    entries: VecDeque<(usize, String)>,
+    capacity: usize,
+    /// Number of the next line
+    next: usize,
+    dedup: Dedup,
+    ignore_space: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl History {
+    /// Create history keeping at most _capacity_ lines
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity.min(1000)),
+            capacity,
+            next: 1,
+            dedup: Dedup::default(),
+            ignore_space: false,
+        }
+    }
+
+    /// Set how repeated lines are treated, [`Dedup::Consecutive`] by default
+    pub fn dedup(mut self, dedup: Dedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Don't record lines starting with space
+    pub fn ignore_space(mut self, enable: bool) -> Self {
+        self.ignore_space = enable;
+        self
+    }
+
+    /// Record _line_. Returns `false` if line is ignored.
+    pub fn push(&mut self, line: &str) -> bool {
+        if self.capacity == 0
+            || line.trim().is_empty()
+            || (self.ignore_space && line.starts_with(' '))
+        {
+            return false;
+        }
+        let line = line.trim();
+        match self.dedup {
+            Dedup::Off => {}
+            Dedup::Consecutive if self.last() == Some(line) => return false,
+            Dedup::Consecutive => {}
+            Dedup::All => self.entries.retain(|(_, e)| e != line),
+        }
+
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((self.next, line.to_owned()));
+        self.next += 1;
+        true
+    }
+
+    /// Line with _number_
+    pub fn get(&self, number: usize) -> Option<&str> {
+        let i = self
+            .entries
+            .binary_search_by_key(&number, |(n, _)| *n)
+            .ok()?;
+        Some(self.entries[i].1.as_str())
+    }
+
+    /// The most recent line
+    pub fn last(&self) -> Option<&str> {
+        self.entries.back().map(|(_, e)| e.as_str())
+    }
+
+    /// Lines with their numbers from the oldest one
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.entries.iter().map(|(n, e)| (*n, e.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Append lines from _reader_, one line per entry
+    pub fn read_from<R: Read>(&mut self, reader: R) -> io::Result<()> {
+        for line in BufReader::new(reader).lines() {
+            self.push(&line?);
+        }
+        Ok(())
+    }
+
+    /// Write all lines to _writer_, one line per entry
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        for (_, line) in self.entries.iter() {
+            writeln!(writer, "{line}")?;
+        }
+        writer.flush()
+    }
+
+    /// Append lines from plain text file at _path_
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        self.read_from(File::open(path)?)
+    }
+
+    /// Save all lines to plain text file at _path_
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_to(File::create(path)?)
+    }
+}
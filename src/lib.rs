@@ -2,11 +2,34 @@
 mod tests;
 
 mod traits;
-pub use traits::DefaultConfig;
+pub use traits::{
+    Config, DefaultConfig, DefaultErrorFormatter, DefaultHelpFormatter, DefaultPrinter,
+    ErrorFormatter, HelpFormatter, Printer,
+};
+
+mod args;
+pub use args::{CleanArgs, CleanCommands};
+#[cfg(feature = "derive")]
+pub use clean_cli_derive::{CleanArgs, CleanCommands};
 
 mod cli;
 pub use cli::*;
 
+mod completion;
+pub use completion::Completion;
+
+mod script;
+pub use script::{ScriptMode, ScriptReport};
+
+mod editor;
+pub use editor::{Editor, LineHelper};
+
+mod history;
+pub use history::{Dedup, History};
+
+mod repl;
+pub use repl::{Input, Repl};
+
 mod command;
 pub use command::*;
 
@@ -14,4 +37,12 @@ mod parameter;
 pub use parameter::*;
 
 mod context;
+pub use context::{Context, ContextUnit};
+
+mod prefix_tree;
+
+mod diagnostic;
+pub use diagnostic::Diagnostic;
+
 mod error;
+pub use error::{Error, ErrorKind, InvalidType, OwnedError, ScriptError, Span, ValueError};
@@ -1,4 +1,5 @@
-use std::fmt::Display;
+use crate::error::InvalidType;
+use std::{any::Any, fmt::Display, marker::PhantomData, rc::Rc, str::FromStr};
 
 /// Contains value for commands and parameters
 #[derive(Debug, Clone)]
@@ -7,6 +8,82 @@ pub enum ArgValue {
     Int(i64),
     Float(f64),
     String(String),
+    List(Vec<ArgValue>),
+    /// Value produced by [`ValueParser`] of [`ArgType::Custom`]
+    Custom(Rc<dyn Any>),
+}
+
+impl Display for ArgValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(v) => v.fmt(f),
+            Self::Int(v) => v.fmt(f),
+            Self::Float(v) => v.fmt(f),
+            Self::String(v) => v.fmt(f),
+            Self::List(v) => {
+                for (i, item) in v.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    item.fmt(f)?;
+                }
+                Ok(())
+            }
+            Self::Custom(_) => f.write_str("<custom>"),
+        }
+    }
+}
+
+macro_rules! impl_try_from_arg_value {
+    ($type:ty, $variant:ident, $name:literal) => {
+        impl From<$type> for ArgValue {
+            fn from(value: $type) -> Self {
+                ArgValue::$variant(value)
+            }
+        }
+
+        impl TryFrom<&ArgValue> for $type {
+            type Error = InvalidType;
+
+            fn try_from(value: &ArgValue) -> std::result::Result<Self, Self::Error> {
+                match value {
+                    ArgValue::$variant(v) => Ok(v.clone()),
+                    _ => Err(InvalidType { expected: $name }),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_arg_value!(bool, Bool, "bool");
+impl_try_from_arg_value!(i64, Int, "int");
+impl_try_from_arg_value!(f64, Float, "float");
+impl_try_from_arg_value!(String, String, "string");
+
+impl From<&str> for ArgValue {
+    fn from(value: &str) -> Self {
+        ArgValue::String(value.to_string())
+    }
+}
+
+impl<V: Into<ArgValue>> From<Vec<V>> for ArgValue {
+    fn from(value: Vec<V>) -> Self {
+        ArgValue::List(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<V> TryFrom<&ArgValue> for Vec<V>
+where
+    V: for<'v> TryFrom<&'v ArgValue, Error = InvalidType>,
+{
+    type Error = InvalidType;
+
+    fn try_from(value: &ArgValue) -> std::result::Result<Self, Self::Error> {
+        match value {
+            ArgValue::List(list) => list.iter().map(V::try_from).collect(),
+            _ => Err(InvalidType { expected: "list" }),
+        }
+    }
 }
 
 /// Set value type for commands and parameters
@@ -17,20 +94,186 @@ pub enum ArgType {
     Int,
     Float,
     String,
+    /// Flag that counts own occurrences, so `-vv --verbose` gives `ArgValue::Int(3)`.
+    /// Inline value is added to the count, so `-v --verbose=2` gives `ArgValue::Int(3)`
+    Count,
+    /// One of listed strings. Value is stored as `ArgValue::String`
+    Choice(Vec<String>),
+    /// Same as [`ArgType::Choice`] but compared case-insensitively.
+    /// Value is stored as listed, regardless of case in command line
+    ChoiceIgnoreCase(Vec<String>),
+    /// User-defined type parsed with [`ValueParser`]. Value is stored as `ArgValue::Custom`
+    Custom(Rc<dyn ValueParser>),
+}
+
+impl ArgType {
+    /// Create [`ArgType::Choice`] from list of strings
+    pub fn choice<I, S>(choices: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::Choice(choices.into_iter().map(Into::into).collect())
+    }
+
+    /// Create [`ArgType::ChoiceIgnoreCase`] from list of strings
+    pub fn choice_ignore_case<I, S>(choices: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::ChoiceIgnoreCase(choices.into_iter().map(Into::into).collect())
+    }
+
+    /// Create [`ArgType::Custom`] with _parser_
+    pub fn custom<P: ValueParser + 'static>(parser: P) -> Self {
+        Self::Custom(Rc::new(parser))
+    }
+
+    /// Value of this type can be set without value token
+    pub(crate) fn is_flag(&self) -> bool {
+        matches!(self, Self::Bool | Self::Count)
+    }
 }
 
 impl Display for ArgType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match *self {
-                Self::Bool => "bool",
-                Self::Int => "int",
-                Self::Float => "float",
-                Self::String => "string",
+        match self {
+            Self::Bool => f.write_str("bool"),
+            Self::Int => f.write_str("int"),
+            Self::Float => f.write_str("float"),
+            Self::String => f.write_str("string"),
+            Self::Count => f.write_str("count"),
+            Self::Choice(choices) | Self::ChoiceIgnoreCase(choices) => {
+                f.write_str(choices.join("|").as_str())
             }
-        )
+            Self::Custom(parser) => f.write_str(parser.type_name().as_str()),
+        }
+    }
+}
+
+/// Parser for user-defined value types.
+///
+/// It is implemented for closures `Fn(&str) -> Result<V, E>` where `E: Display`,
+/// and for [`FromStrParser`] that uses [`FromStr`] implementation of type.
+///
+/// # Example
+///
+/// ```rust
+/// use clean_cli::*;
+/// use std::net::Ipv4Addr;
+///
+/// let cli = <Cli<DefaultConfig<Option<Ipv4Addr>>>>::builder()
+///     .command(CommandBuilder::with_name("connect")
+///         .parameter(Parameter::with_name("ip")
+///             .value_parser(FromStrParser::<Ipv4Addr>::new()))
+///         .parameter(Parameter::with_name("port")
+///             .value_parser(|arg: &str| match arg.parse::<u16>() {
+///                 Ok(0) | Err(_) => Err("port must be in range 1..65535"),
+///                 Ok(port) => Ok(port),
+///             }))
+///         .handler(|ctx| match ctx.command_units().last()?.parameters().get("ip") {
+///             Some((_, ArgValue::Custom(ip))) => ip.downcast_ref::<Ipv4Addr>().copied(),
+///             _ => None,
+///         })
+///     )
+///     .build();
+///
+/// assert_eq!(
+///     cli.exec("connect --ip 127.0.0.1 --port 80").unwrap(),
+///     Some(Ipv4Addr::LOCALHOST)
+/// );
+/// assert!(cli.exec("connect --port 0").is_err());
+/// ```
+pub trait ValueParser {
+    /// Parse _arg_ or return message describing why it is invalid
+    fn parse(&self, arg: &str) -> std::result::Result<Rc<dyn Any>, String>;
+
+    /// Type name shown in help
+    fn type_name(&self) -> String {
+        "value".to_string()
+    }
+}
+
+impl<F, V, E> ValueParser for F
+where
+    F: Fn(&str) -> std::result::Result<V, E>,
+    V: Any,
+    E: Display,
+{
+    fn parse(&self, arg: &str) -> std::result::Result<Rc<dyn Any>, String> {
+        match self(arg) {
+            Ok(v) => Ok(Rc::new(v)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Debug for dyn ValueParser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ValueParser<{}>", self.type_name())
+    }
+}
+
+impl PartialEq for dyn ValueParser {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(self, other)
+    }
+}
+
+/// [`ValueParser`] for types implementing [`FromStr`]
+pub struct FromStrParser<V>(PhantomData<V>);
+
+impl<V> FromStrParser<V> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<V> Default for FromStrParser<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> ValueParser for FromStrParser<V>
+where
+    V: FromStr + Any,
+    V::Err: Display,
+{
+    fn parse(&self, arg: &str) -> std::result::Result<Rc<dyn Any>, String> {
+        match V::from_str(arg) {
+            Ok(v) => Ok(Rc::new(v)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn type_name(&self) -> String {
+        let name = std::any::type_name::<V>();
+        name.rsplit("::").next().unwrap_or(name).to_lowercase()
+    }
+}
+
+/// Source of completion candidates for dynamic values like file names or object IDs.
+///
+/// It is implemented for closures `Fn(&str) -> Vec<String>`.
+/// Completer receives typed part of the value and returns candidates to replace it.
+pub trait Completer {
+    fn complete(&self, prefix: &str) -> Vec<String>;
+}
+
+impl<F> Completer for F
+where
+    F: Fn(&str) -> Vec<String>,
+{
+    fn complete(&self, prefix: &str) -> Vec<String> {
+        self(prefix)
+    }
+}
+
+impl std::fmt::Debug for dyn Completer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Completer")
     }
 }
 
@@ -40,6 +283,55 @@ pub struct Parameter {
     pub(crate) name: String,
     pub(crate) value_type: ArgType,
     pub(crate) description: String,
+    pub(crate) required: bool,
+    pub(crate) default: Option<ArgValue>,
+    pub(crate) multiple: bool,
+    pub(crate) delimiter: Option<char>,
+    pub(crate) min_occurrences: usize,
+    pub(crate) max_occurrences: Option<usize>,
+    pub(crate) negatable: bool,
+    pub(crate) completer: Option<Rc<dyn Completer>>,
+}
+
+impl Parameter {
+    /// Parameter collects all its values into [`ArgValue::List`]
+    pub(crate) fn is_multiple(&self) -> bool {
+        self.multiple || self.delimiter.is_some()
+    }
+}
+
+/// Command positional value
+#[derive(Debug)]
+pub struct Positional {
+    pub(crate) name: String,
+    pub(crate) value_type: ArgType,
+    pub(crate) required: bool,
+    pub(crate) variadic: bool,
+}
+
+impl Positional {
+    /// Format positional for usage line: `<name>` for required,
+    /// `[name]` for optional and `[name...]` for variadic values.
+    pub(crate) fn usage(&self) -> String {
+        if self.variadic {
+            format!("[{}...]", self.name)
+        } else if self.required {
+            format!("<{}>", self.name)
+        } else {
+            format!("[{}]", self.name)
+        }
+    }
+
+    /// Format positional with its value type for hints, like [`usage`](Self::usage)
+    pub(crate) fn hint(&self) -> String {
+        if self.variadic {
+            format!("[{}: {}...]", self.name, self.value_type)
+        } else if self.required {
+            format!("<{}: {}>", self.name, self.value_type)
+        } else {
+            format!("[{}: {}]", self.name, self.value_type)
+        }
+    }
 }
 
 /// Buildr for command parameter
@@ -49,11 +341,19 @@ pub struct ParameterBuilder<'a> {
     pub(crate) aliases: Vec<String>,
     pub(crate) description: Option<&'a str>,
     pub(crate) value_type: ArgType,
+    pub(crate) required: bool,
+    pub(crate) default: Option<ArgValue>,
+    pub(crate) multiple: bool,
+    pub(crate) delimiter: Option<char>,
+    pub(crate) min_occurrences: usize,
+    pub(crate) max_occurrences: Option<usize>,
+    pub(crate) negatable: bool,
+    pub(crate) completer: Option<Rc<dyn Completer>>,
 }
 
 impl Parameter {
     /// Create parameter builder with name
-    pub fn with_name(name: &str) -> ParameterBuilder {
+    pub fn with_name(name: &str) -> ParameterBuilder<'_> {
         ParameterBuilder {
             name: name.to_string(),
             ..Default::default()
@@ -68,6 +368,12 @@ impl<'a> ParameterBuilder<'a> {
         self
     }
 
+    /// Set parser for user-defined value type.
+    /// This is a shortcut for `value_type(ArgType::custom(parser))`
+    pub fn value_parser<P: ValueParser + 'static>(self, parser: P) -> Self {
+        self.value_type(ArgType::custom(parser))
+    }
+
     /// Add alias for parameter.
     /// If alias consist from single charceter than this parameter will be used as short
     pub fn alias(mut self, alias: &'a str) -> Self {
@@ -80,4 +386,60 @@ impl<'a> ParameterBuilder<'a> {
         self.description = Some(text);
         self
     }
+
+    /// Make parameter mandatory.
+    /// Command fails with [`Error::MissingRequired`](crate::Error::MissingRequired) if parameter is not set.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set value used when parameter is not set.
+    /// Value of other type is parsed from its text as value of the parameter.
+    /// Single value of [`multiple`](Self::multiple) parameter is set as a list of one value.
+    /// # Panic
+    /// Adding parameter to command panics if value can not be parsed
+    pub fn default_value(mut self, value: ArgValue) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    /// Allow to set parameter several times.
+    /// All values are collected into [`ArgValue::List`] in order of appearance.
+    pub fn multiple(mut self) -> Self {
+        self.multiple = true;
+        self
+    }
+
+    /// Split every value of parameter by _delimiter_, so `--tags a,b,c` gives three values.
+    /// Implies [`multiple`](Self::multiple).
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Set minimum count of parameter occurrences in command line
+    pub fn min_occurrences(mut self, min: usize) -> Self {
+        self.min_occurrences = min;
+        self
+    }
+
+    /// Set maximum count of parameter occurrences in command line
+    pub fn max_occurrences(mut self, max: usize) -> Self {
+        self.max_occurrences = Some(max);
+        self
+    }
+
+    /// Add `--no-<name>` form that sets boolean parameter to `false`
+    pub fn negatable(mut self) -> Self {
+        self.negatable = true;
+        self
+    }
+
+    /// Set source of completion candidates for values of parameter.
+    /// See [`Cli::complete`](crate::Cli::complete)
+    pub fn completer<C: Completer + 'static>(mut self, completer: C) -> Self {
+        self.completer = Some(Rc::new(completer));
+        self
+    }
 }
@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+/// Index of names for search by prefix
+#[derive(Debug, Default)]
+pub(crate) struct PrefixTree {
+    root: Node,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: BTreeMap<char, Node>,
+    /// Name that ends in this node
+    name: Option<String>,
+}
+
+impl PrefixTree {
+    pub(crate) fn insert(&mut self, name: &str) {
+        let node = name.chars().fold(&mut self.root, |node, c| {
+            node.children.entry(c).or_default()
+        });
+        node.name = Some(name.to_owned());
+    }
+
+    /// All names starting with _prefix_ in alphabetical order
+    pub(crate) fn find(&self, prefix: &str) -> Vec<&str> {
+        let mut node = &self.root;
+        for c in prefix.chars() {
+            match node.children.get(&c) {
+                Some(child) => node = child,
+                None => return vec![],
+            }
+        }
+
+        let mut names = vec![];
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            names.extend(node.name.as_deref());
+            stack.extend(node.children.values().rev());
+        }
+        names
+    }
+}
+
+impl<'a> FromIterator<&'a String> for PrefixTree {
+    fn from_iter<I: IntoIterator<Item = &'a String>>(iter: I) -> Self {
+        let mut tree = Self::default();
+        iter.into_iter().for_each(|name| tree.insert(name));
+        tree
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{
+    cli::Cli,
+    editor::{Editor, LineHelper},
+    error::Error,
+    history::History,
+    traits::Config,
+};
+use std::{
+    io::{self, BufRead, Stdout, Write},
+    path::PathBuf,
+};
+
+/// Source of lines for [`Repl`].
+///
+/// It is implemented for [`Editor`] and for any [`BufRead`] that prints prompt
+/// to output and reads lines one by one.
+pub trait Input {
+    /// Show _prompt_ and read next line without line ending.
+    /// _helper_ may be used to complete words and show hints.
+    /// Returns `None` at the end of input.
+    fn read_line(
+        &mut self,
+        prompt: &str,
+        output: &mut dyn Write,
+        helper: &dyn LineHelper,
+    ) -> io::Result<Option<String>>;
+}
+
+impl<R: BufRead> Input for R {
+    fn read_line(
+        &mut self,
+        prompt: &str,
+        output: &mut dyn Write,
+        _helper: &dyn LineHelper,
+    ) -> io::Result<Option<String>> {
+        output.write_all(prompt.as_bytes())?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if BufRead::read_line(self, &mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+}
+
+type ResultHook<T> = Box<dyn FnMut(<T as Config>::Result)>;
+type ErrorHook<T> = Box<dyn FnMut(&Error<<T as Config>::HandlerError>)>;
+
+/// Read-eval-print loop that executes lines with [`Cli`].
+///
+/// Empty lines are skipped, loop ends on exit command or at the end of input.
+/// If [`History`] is set, lines are recorded and `history`, `!n` and `!!`
+/// built-in commands are available to show and re-run recorded lines.
+/// Errors are printed by [`Cli`] itself if [`print_error`](crate::CliBuilder::print_error)
+/// is enabled and are passed to [`on_error`](Self::on_error) hook.
+///
+/// # Example
+///
+/// ```rust
+/// use clean_cli::*;
+/// use std::{cell::RefCell, rc::Rc};
+///
+/// let cli = <Cli<DefaultConfig<i64>>>::builder()
+///     .command(CommandBuilder::with_name("double")
+///         .use_value(ArgType::Int)
+///         .handler(|ctx| ctx.value_as::<i64>().unwrap() * 2)
+///     )
+///     .build();
+///
+/// let results = Rc::new(RefCell::new(vec![]));
+/// let sink = results.clone();
+/// let mut repl = Repl::new(cli)
+///     .prompt("> ")
+///     .set_input("double 2\n\ndouble 21\nexit\ndouble 0\n".as_bytes())
+///     .set_output(Vec::new())
+///     .on_result(move |r| sink.borrow_mut().push(r));
+///
+/// repl.run().unwrap();
+/// assert_eq!(*results.borrow(), [4, 42]);
+/// assert_eq!(repl.output(), b"> > > > ");
+/// ```
+pub struct Repl<T: Config, I: Input = Editor, W: Write = Stdout> {
+    cli: Cli<T>,
+    input: I,
+    output: W,
+    prompt: String,
+    exit_commands: Vec<String>,
+    on_result: Option<ResultHook<T>>,
+    on_error: Option<ErrorHook<T>>,
+    history: Option<History>,
+    history_file: Option<PathBuf>,
+}
+
+impl<T: Config> Repl<T> {
+    /// Create loop reading stdin with [`Editor`] and writing prompt to stdout.
+    /// Default prompt is `"> "` and exit commands are `exit` and `quit`.
+    pub fn new(cli: Cli<T>) -> Self {
+        Self {
+            cli,
+            input: Editor::new(),
+            output: io::stdout(),
+            prompt: "> ".to_owned(),
+            exit_commands: vec!["exit".to_owned(), "quit".to_owned()],
+            on_result: None,
+            on_error: None,
+            history: None,
+            history_file: None,
+        }
+    }
+}
+
+impl<T: Config, I: Input, W: Write> Repl<T, I, W> {
+    /// Set text shown before every line
+    pub fn prompt(mut self, prompt: &str) -> Self {
+        self.prompt = prompt.to_owned();
+        self
+    }
+
+    /// Set commands that end the loop instead of default `exit` and `quit`
+    pub fn exit_commands<S: ToString>(mut self, commands: impl IntoIterator<Item = S>) -> Self {
+        self.exit_commands = commands.into_iter().map(|c| c.to_string()).collect();
+        self
+    }
+
+    /// Set source of lines
+    pub fn set_input<I2: Input>(self, input: I2) -> Repl<T, I2, W> {
+        Repl {
+            cli: self.cli,
+            input,
+            output: self.output,
+            prompt: self.prompt,
+            exit_commands: self.exit_commands,
+            on_result: self.on_result,
+            on_error: self.on_error,
+            history: self.history,
+            history_file: self.history_file,
+        }
+    }
+
+    /// Set output for prompt
+    pub fn set_output<W2: Write>(self, output: W2) -> Repl<T, I, W2> {
+        Repl {
+            cli: self.cli,
+            input: self.input,
+            output,
+            prompt: self.prompt,
+            exit_commands: self.exit_commands,
+            on_result: self.on_result,
+            on_error: self.on_error,
+            history: self.history,
+            history_file: self.history_file,
+        }
+    }
+
+    /// Set hook called with result of every executed line
+    pub fn on_result<F: FnMut(T::Result) + 'static>(mut self, f: F) -> Self {
+        self.on_result = Some(Box::new(f));
+        self
+    }
+
+    /// Set hook called with error of every failed line
+    pub fn on_error<F: FnMut(&Error<T::HandlerError>) + 'static>(mut self, f: F) -> Self {
+        self.on_error = Some(Box::new(f));
+        self
+    }
+
+    /// Record lines into _history_ and enable `history`, `!n` and `!!` commands
+    pub fn set_history(mut self, history: History) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Load history from file at _path_ when loop starts and save it there when loop ends.
+    /// Enables default [`History`] if it is not set.
+    pub fn history_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.history.get_or_insert_with(History::default);
+        self.history_file = Some(path.into());
+        self
+    }
+
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
+    pub fn cli(&self) -> &Cli<T> {
+        &self.cli
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    /// Run loop until exit command or end of input
+    pub fn run(&mut self) -> io::Result<()> {
+        if let (Some(history), Some(path)) = (self.history.as_mut(), self.history_file.as_ref()) {
+            match history.load(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+
+        let result = self.run_loop();
+
+        if let (Some(history), Some(path)) = (self.history.as_ref(), self.history_file.as_ref()) {
+            history.save(path)?;
+        }
+        result
+    }
+
+    fn run_loop(&mut self) -> io::Result<()> {
+        while let Some(line) = self
+            .input
+            .read_line(&self.prompt, &mut self.output, &self.cli)?
+        {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if self.exit_commands.iter().any(|c| c == trimmed) {
+                break;
+            }
+
+            let Some(history) = self.history.as_mut() else {
+                self.exec(trimmed);
+                continue;
+            };
+            if let Some(event) = trimmed.strip_prefix('!') {
+                let entry = match event {
+                    "!" => history.last(),
+                    n => n.parse().ok().and_then(|n| history.get(n)),
+                };
+                let Some(entry) = entry.map(str::to_owned) else {
+                    writeln!(self.output, "{trimmed}: event not found")?;
+                    continue;
+                };
+                writeln!(self.output, "{entry}")?;
+                history.push(&entry);
+                self.exec(&entry);
+            } else if trimmed == "history" {
+                history.push(&line);
+                for (n, entry) in history.iter() {
+                    writeln!(self.output, "{n:>5}  {entry}")?;
+                }
+            } else {
+                history.push(&line);
+                self.exec(trimmed);
+            }
+        }
+        Ok(())
+    }
+
+    fn exec(&mut self, line: &str) {
+        for result in self.cli.exec_chain(line) {
+            match result {
+                Ok(result) => {
+                    if let Some(f) = self.on_result.as_mut() {
+                        f(result);
+                    }
+                }
+                Err(error) => {
+                    if let Some(f) = self.on_error.as_mut() {
+                        f(&error);
+                    }
+                }
+            }
+        }
+    }
+}
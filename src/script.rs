@@ -0,0 +1,183 @@
+use crate::{
+    cli::Cli,
+    error::{OwnedError, ScriptError},
+    traits::Config,
+};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+/// What [`Cli::exec_script`] does when a line fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScriptMode {
+    /// Stop at the first failed line
+    #[default]
+    StopOnError,
+    /// Execute all lines and collect errors
+    Continue,
+}
+
+/// Results of successfully executed script lines and errors of failed ones
+#[derive(Debug)]
+pub struct ScriptReport<R> {
+    pub results: Vec<R>,
+    pub errors: Vec<ScriptError>,
+}
+
+impl<R> ScriptReport<R> {
+    /// All lines are executed without errors
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl<T: Config> Cli<T> {
+    /// Execute script read from _reader_ line by line with [`exec_chain`](Self::exec_chain).
+    ///
+    /// Blank lines and lines starting with `#` are skipped. Line ending with `\`
+    /// is continued on the next line. Line fails if the last executed command
+    /// of its chain fails, so `a || b` is not an error when `b` succeeds.
+    /// Errors refer to the line and column where the failed part of the command is written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use clean_cli::*;
+    ///
+    /// let cli = <Cli<DefaultConfig<i64>>>::builder()
+    ///     .command(CommandBuilder::with_name("sum")
+    ///         .variadic("numbers", ArgType::Int)
+    ///         .handler(|ctx| ctx.get_or::<Vec<i64>>("numbers", vec![]).unwrap().iter().sum())
+    ///     )
+    ///     .build();
+    ///
+    /// let script = "# setup\nsum 1 2\n\nsum 1 \\\n  x\nsum 3\n";
+    ///
+    /// let report = cli.exec_script(script.as_bytes(), ScriptMode::Continue).unwrap();
+    /// assert_eq!(report.results, [3, 3]);
+    /// assert_eq!(
+    ///     report.errors[0].to_string(),
+    ///     "line 5, column 3: Parse int error: invalid digit found in string"
+    /// );
+    ///
+    /// let report = cli.exec_script(script.as_bytes(), ScriptMode::StopOnError).unwrap();
+    /// assert_eq!(report.results, [3]);
+    /// assert_eq!(report.errors.len(), 1);
+    /// ```
+    pub fn exec_script<R: Read>(
+        &self,
+        reader: R,
+        mode: ScriptMode,
+    ) -> io::Result<ScriptReport<T::Result>> {
+        self.run_script(reader, mode, None)
+    }
+
+    /// Execute script file at _path_ like [`exec_script`](Self::exec_script).
+    /// Errors refer to the file.
+    pub fn exec_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        mode: ScriptMode,
+    ) -> io::Result<ScriptReport<T::Result>> {
+        let path = path.as_ref();
+        self.run_script(File::open(path)?, mode, Some(path.to_owned()))
+    }
+
+    fn run_script<R: Read>(
+        &self,
+        reader: R,
+        mode: ScriptMode,
+        file: Option<PathBuf>,
+    ) -> io::Result<ScriptReport<T::Result>> {
+        let mut report = ScriptReport {
+            results: vec![],
+            errors: vec![],
+        };
+        let mut lines = BufReader::new(reader).lines().enumerate();
+
+        while let Some((index, line)) = lines.next() {
+            let line = line?;
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let mut joined = ScriptLine::default();
+            let mut continued = joined.push(index + 1, &line);
+            while continued {
+                match lines.next() {
+                    Some((index, line)) => continued = joined.push(index + 1, &line?),
+                    None => break,
+                }
+            }
+
+            // line fails only if the last executed command of the chain fails,
+            // errors handled by `||` are not reported
+            let mut results = self.exec_chain(&joined.text);
+            let last = results.pop();
+            report
+                .results
+                .extend(results.into_iter().filter_map(Result::ok));
+            match last {
+                Some(Ok(result)) => report.results.push(result),
+                Some(Err(error)) => {
+                    let error = OwnedError::from(error);
+                    let (line, column) = joined.locate(error.range().map(|r| r.start));
+                    report.errors.push(ScriptError {
+                        file: file.clone(),
+                        line,
+                        column,
+                        error,
+                    });
+                    if mode == ScriptMode::StopOnError {
+                        break;
+                    }
+                }
+                None => {}
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// Command joined from continued script lines
+#[derive(Default)]
+struct ScriptLine {
+    text: String,
+    /// Offset in text and number of every joined line
+    lines: Vec<(usize, usize)>,
+}
+
+impl ScriptLine {
+    /// Append line with _number_. Returns `true` if it is continued on the next line.
+    fn push(&mut self, number: usize, line: &str) -> bool {
+        let backslashes = line.len() - line.trim_end_matches('\\').len();
+        let continued = backslashes % 2 == 1;
+
+        self.lines.push((self.text.len(), number));
+        match continued {
+            true => self.text.push_str(&line[..line.len() - 1]),
+            false => self.text.push_str(line),
+        }
+        continued
+    }
+
+    /// Line number and column of byte _offset_ in text
+    fn locate(&self, offset: Option<usize>) -> (usize, Option<usize>) {
+        let first = self.lines[0].1;
+        let Some(offset) = offset else {
+            return (first, None);
+        };
+        let (start, number) = self
+            .lines
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= offset)
+            .copied()
+            .unwrap_or((0, first));
+        let column = self.text[start..offset].chars().count() + 1;
+        (number, Some(column))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     error::Error,
-    traits::{Config, DefaultHelpFormatter, Printer},
+    traits::{Config, DefaultErrorFormatter, DefaultHelpFormatter, Printer},
     ArgType, ArgValue, Cli, CommandBuilder, Parameter,
 };
 use assert2::{check, let_assert};
@@ -15,7 +15,9 @@ use std::{
 pub struct Test<R>(PhantomData<R>);
 impl<R: Default + Debug + 'static> Config for Test<R> {
     type Result = R;
+    type HandlerError = String;
     type HelpFormatter = DefaultHelpFormatter;
+    type ErrorFormatter = DefaultErrorFormatter;
     type PrinterInput = String;
     type Printer = TestPrinter<Self>;
 }
@@ -1404,6 +1406,7 @@ fn sub_command_help() {
     assert_eq!(
         help_text.borrow().as_str(),
         r"Help:
+  Usage: cmd [value]
   Parameters:
     --bool,-b,--bb      <bool>  Boolean param
     --int,-i,--ii       <int>   Integer param
@@ -1434,3 +1437,1898 @@ fn sub_command() {
     assert!(res.is_ok());
     assert!(res.unwrap());
 }
+
+#[test]
+fn command_with_inline_param_values() {
+    let cli = <Cli<Test<(bool, i64, String)>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("bool")
+                        .value_type(ArgType::Bool)
+                        .alias("b"),
+                )
+                .parameter(
+                    Parameter::with_name("int")
+                        .value_type(ArgType::Int)
+                        .alias("i"),
+                )
+                .parameter(
+                    Parameter::with_name("string")
+                        .value_type(ArgType::String)
+                        .alias("s"),
+                )
+                .handler(|ctx| {
+                    let params = ctx.units.last().unwrap().parameters();
+                    let flag = match params.get("bool") {
+                        Some((_, ArgValue::Bool(v))) => *v,
+                        _ => false,
+                    };
+                    let int = match params.get("int") {
+                        Some((_, ArgValue::Int(v))) => *v,
+                        _ => 0,
+                    };
+                    let string = match params.get("string") {
+                        Some((_, ArgValue::String(v))) => v.clone(),
+                        _ => String::new(),
+                    };
+                    (flag, int, string)
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("cmd --int=42 --string=a=b --bool=yes"));
+    check!(r == (true, 42, "a=b".to_string()));
+
+    let_assert!(Ok(r) = cli.exec("cmd -i=42 -s= -b=off"));
+    check!(r == (false, 42, String::new()));
+
+    let_assert!(Ok(r) = cli.exec("cmd -bi=-7"));
+    check!(r == (true, -7, String::new()));
+
+    let_assert!(Ok(r) = cli.exec("cmd -is=abc 42"));
+    check!(r == (false, 42, "abc".to_string()));
+}
+
+#[test]
+fn command_with_inline_param_values_errors() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("int")
+                        .value_type(ArgType::Int)
+                        .alias("i"),
+                )
+                .handler(|_| {
+                    panic!("handler must no execute");
+                }),
+        )
+        .build();
+
+    let_assert!(Err(Error::ParseInt(span, _)) = cli.exec("cmd --int=abc"));
+    check!(span.arg() == "abc");
+    check!(span.begin == 10);
+
+    let_assert!(Err(Error::ParseInt(span, _)) = cli.exec("cmd -i=4x2"));
+    check!(span.arg() == "4x2");
+
+    let_assert!(Err(Error::NotParameter { span, .. }) = cli.exec("cmd --unknown=42"));
+    check!(span.arg() == "--unknown");
+
+    let_assert!(Err(Error::NotParameter { span, .. }) = cli.exec("cmd -x=42"));
+    check!(span.arg() == "-x");
+}
+
+#[test]
+fn command_with_escaped_and_quoted_values() {
+    let cli = <Cli<Test<String>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("string")
+                        .value_type(ArgType::String)
+                        .alias("s"),
+                )
+                .handler(
+                    |ctx| match ctx.units.last().unwrap().parameters().get("string") {
+                        Some((_, ArgValue::String(v))) => v.clone(),
+                        _ => panic!("parameter not found"),
+                    },
+                ),
+        )
+        .build();
+
+    let_assert!(Ok(s) = cli.exec(r#"cmd --string="a b""#));
+    check!(s == "a b");
+
+    let_assert!(Ok(s) = cli.exec(r#"cmd -s a\ \"b\""#));
+    check!(s == "a \"b\"");
+
+    let_assert!(Ok(s) = cli.exec(r#"cmd -s 'a'"b"c"#));
+    check!(s == "abc");
+
+    let_assert!(Err(Error::UnterminatedQuote(span)) = cli.exec("cmd -s \"abc"));
+    check!(span.arg() == "\"abc");
+}
+
+#[test]
+fn command_with_negative_values() {
+    let cli = <Cli<Test<(i64, f64)>>>::builder()
+        .command(
+            CommandBuilder::with_name("int")
+                .use_value(ArgType::Int)
+                .parameter(
+                    Parameter::with_name("float")
+                        .value_type(ArgType::Float)
+                        .alias("f"),
+                )
+                .handler(|ctx| {
+                    let unit = ctx.units.last().unwrap();
+                    let int = match unit.value() {
+                        Some(ArgValue::Int(v)) => *v,
+                        _ => 0,
+                    };
+                    let float = match unit.parameters().get("float") {
+                        Some((_, ArgValue::Float(v))) => *v,
+                        _ => 0.0,
+                    };
+                    (int, float)
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("int -5"));
+    check!(r == (-5, 0.0));
+
+    let_assert!(Ok(r) = cli.exec("int -f -4.2e3 -7"));
+    check!(r == (-7, -4200.0));
+
+    let_assert!(Ok(r) = cli.exec("int --float -.5"));
+    check!(r == (0, -0.5));
+
+    let_assert!(Err(Error::ParseInt(span, _)) = cli.exec("int -4.2"));
+    check!(span.arg() == "-4.2");
+
+    let_assert!(Err(Error::NotValue(span)) = cli.exec("int -f -x"));
+    check!(span.arg() == "-x");
+
+    let_assert!(Err(Error::NotParameter { .. }) = cli.exec("int -x"));
+}
+
+#[test]
+fn command_with_end_of_options() {
+    let cli = <Cli<Test<String>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .use_value(ArgType::String)
+                .parameter(Parameter::with_name("flag").alias("f"))
+                .handler(|ctx| {
+                    let unit = ctx.units.last().unwrap();
+                    check!(unit.parameters().get("flag").is_none());
+                    match unit.value() {
+                        Some(ArgValue::String(v)) => v.clone(),
+                        _ => String::new(),
+                    }
+                }),
+        )
+        .command(CommandBuilder::with_name("empty").handler(|_| String::new()))
+        .build();
+
+    let_assert!(Ok(s) = cli.exec("cmd -- -f"));
+    check!(s == "-f");
+
+    let_assert!(Ok(s) = cli.exec("cmd -- --"));
+    check!(s == "--");
+
+    let_assert!(Ok(s) = cli.exec("cmd --"));
+    check!(s == "");
+
+    let_assert!(Err(Error::NotCommand { span, .. }) = cli.exec("empty -- -f"));
+    check!(span.arg() == "-f");
+}
+
+#[test]
+fn command_with_positional_values() {
+    let cli = <Cli<Test<Vec<String>>>>::builder()
+        .command(
+            CommandBuilder::with_name("cp")
+                .positional("src", ArgType::String)
+                .positional("dst", ArgType::String)
+                .optional_positional("mode", ArgType::Int)
+                .variadic("files", ArgType::String)
+                .parameter(Parameter::with_name("force").alias("f"))
+                .handler(|ctx| {
+                    let unit = ctx.units.last().unwrap();
+                    check!(unit.value().is_some());
+                    check!(format!("{:?}", unit.value()) == format!("{:?}", unit.value_of("src")));
+                    let mut result: Vec<_> = unit
+                        .values()
+                        .iter()
+                        .map(|(p, v)| format!("{}={v:?}", p.name))
+                        .collect();
+                    result.push(format!("files:{}", unit.values_of("files").len()));
+                    result
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("cp a b"));
+    check!(r == ["src=String(\"a\")", "dst=String(\"b\")", "files:0"]);
+
+    let_assert!(Ok(r) = cli.exec("cp a -f b 7"));
+    check!(
+        r == [
+            "src=String(\"a\")",
+            "dst=String(\"b\")",
+            "mode=Int(7)",
+            "files:0"
+        ]
+    );
+
+    let_assert!(Ok(r) = cli.exec("cp a b 7 x -f y -- -z"));
+    check!(
+        r == [
+            "src=String(\"a\")",
+            "dst=String(\"b\")",
+            "mode=Int(7)",
+            "files=String(\"x\")",
+            "files=String(\"y\")",
+            "files=String(\"-z\")",
+            "files:3"
+        ]
+    );
+
+    let_assert!(Err(e) = cli.exec("cp a"));
+    check!(
+        e == Error::MissingValue {
+            command: "cp".to_string(),
+            value: "dst".to_string()
+        }
+    );
+
+    let_assert!(Err(Error::ParseInt(span, _)) = cli.exec("cp a b c"));
+    check!(span.arg() == "c");
+}
+
+#[test]
+fn command_with_too_many_values() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .use_value(ArgType::Int)
+                .handler(|_| {}),
+        )
+        .command(CommandBuilder::with_name("empty").handler(|_| {}))
+        .build();
+
+    check!(let Ok(_) = cli.exec("cmd"));
+    check!(let Ok(_) = cli.exec("cmd 1"));
+
+    let_assert!(Err(Error::UnexpectedValue(span)) = cli.exec("cmd 1 2"));
+    check!(span.arg() == "2");
+
+    let_assert!(Err(Error::NotCommand { span, .. }) = cli.exec("empty 1"));
+    check!(span.arg() == "1");
+}
+
+#[test]
+#[should_panic]
+fn required_positional_after_optional() {
+    let _ = <CommandBuilder<Test<()>>>::with_name("cmd")
+        .optional_positional("a", ArgType::Int)
+        .positional("b", ArgType::Int);
+}
+
+#[test]
+#[should_panic]
+fn positional_after_variadic() {
+    let _ = <CommandBuilder<Test<()>>>::with_name("cmd")
+        .variadic("a", ArgType::Int)
+        .optional_positional("b", ArgType::Int);
+}
+
+#[test]
+fn positional_values_help() {
+    let help_text = Rc::new(RefCell::new(String::new()));
+    let printer = TestPrinter(help_text.clone());
+    let cli = <Cli<Test<()>>>::builder()
+        .set_printer(printer)
+        .print_help(true)
+        .command(
+            CommandBuilder::with_name("cp")
+                .positional("src", ArgType::String)
+                .optional_positional("dst", ArgType::String)
+                .variadic("files", ArgType::String)
+                .subcommand(CommandBuilder::with_name("sub").handler(|_| {})),
+        )
+        .build();
+
+    assert!(cli.exec("cp help").is_ok());
+    assert_eq!(
+        help_text.borrow().as_str(),
+        r"Help:
+  Usage: cp <src> [dst] [files...]
+  Subcommands:
+    help                 This help
+    sub                  "
+    );
+}
+
+#[test]
+fn command_with_required_and_default_params() {
+    let cli = <Cli<Test<(i64, String)>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("int")
+                        .value_type(ArgType::Int)
+                        .alias("i")
+                        .default_value(ArgValue::Int(10)),
+                )
+                .parameter(
+                    Parameter::with_name("string")
+                        .value_type(ArgType::String)
+                        .alias("s")
+                        .required(),
+                )
+                .handler(|ctx| {
+                    let params = ctx.units.last().unwrap().parameters();
+                    let_assert!(Some((_, ArgValue::Int(int))) = params.get("int"));
+                    let_assert!(Some((_, ArgValue::String(string))) = params.get("string"));
+                    (*int, string.clone())
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("cmd -s abc"));
+    check!(r == (10, "abc".to_string()));
+
+    let_assert!(Ok(r) = cli.exec("cmd -i 42 --string=abc"));
+    check!(r == (42, "abc".to_string()));
+
+    let_assert!(Err(e) = cli.exec("cmd -i 42"));
+    check!(
+        e == Error::MissingRequired {
+            command: "cmd".to_string(),
+            parameter: "string".to_string()
+        }
+    );
+}
+
+#[test]
+fn required_and_default_params_help() {
+    let help_text = Rc::new(RefCell::new(String::new()));
+    let printer = TestPrinter(help_text.clone());
+    let cli = <Cli<Test<()>>>::builder()
+        .set_printer(printer)
+        .print_help(true)
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("int")
+                        .value_type(ArgType::Int)
+                        .default_value(ArgValue::Int(10))
+                        .description("Integer param"),
+                )
+                .parameter(
+                    Parameter::with_name("string")
+                        .value_type(ArgType::String)
+                        .required(),
+                )
+                .subcommand(CommandBuilder::with_name("sub").handler(|_| {})),
+        )
+        .build();
+
+    assert!(cli.exec("cmd help").is_ok());
+    assert_eq!(
+        help_text.borrow().as_str(),
+        r"Help:
+  Parameters:
+    --int               <int>   Integer param [default: 10]
+    --string            <string> (required)
+----------------------------------------
+  Subcommands:
+    help                 This help
+    sub                  "
+    );
+}
+
+#[test]
+#[should_panic]
+fn required_param_with_default() {
+    let _ = <CommandBuilder<Test<()>>>::with_name("cmd").parameter(
+        Parameter::with_name("int")
+            .value_type(ArgType::Int)
+            .required()
+            .default_value(ArgValue::Int(1)),
+    );
+}
+
+#[test]
+#[should_panic(expected = "default value \"abc\" of parameter \"int\" is not <int>")]
+fn invalid_default_value() {
+    let _ = <CommandBuilder<Test<()>>>::with_name("cmd").parameter(
+        Parameter::with_name("int")
+            .value_type(ArgType::Int)
+            .default_value(ArgValue::String("abc".to_owned())),
+    );
+}
+
+#[test]
+#[should_panic(expected = "default value \"xml\" of parameter \"format\" is not <json|yaml>")]
+fn invalid_default_choice() {
+    let _ = <CommandBuilder<Test<()>>>::with_name("cmd").parameter(
+        Parameter::with_name("format")
+            .value_type(ArgType::choice(["json", "yaml"]))
+            .default_value(ArgValue::String("xml".to_owned())),
+    );
+}
+
+#[test]
+fn default_value_parsed_from_text() {
+    let cli = <Cli<Test<(i64, f64)>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("int")
+                        .value_type(ArgType::Int)
+                        .default_value(ArgValue::String("10".to_owned())),
+                )
+                .parameter(
+                    Parameter::with_name("float")
+                        .value_type(ArgType::Float)
+                        .default_value(ArgValue::Int(2)),
+                )
+                .handler(|ctx| (ctx.get("int").unwrap(), ctx.get("float").unwrap())),
+        )
+        .build();
+
+    let_assert!(Ok((10, 2.0)) = cli.exec("cmd"));
+}
+
+#[test]
+fn command_with_multiple_params() {
+    let cli = <Cli<Test<String>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("tag")
+                        .value_type(ArgType::String)
+                        .alias("t")
+                        .multiple(),
+                )
+                .parameter(
+                    Parameter::with_name("ints")
+                        .value_type(ArgType::Int)
+                        .alias("i")
+                        .delimiter(','),
+                )
+                .handler(|ctx| {
+                    let params = ctx.units.last().unwrap().parameters();
+                    let tags = match params.get("tag") {
+                        Some((_, v @ ArgValue::List(_))) => v.to_string(),
+                        Some(_) => panic!("list expected"),
+                        None => String::new(),
+                    };
+                    let ints = match params.get("ints") {
+                        Some((_, v @ ArgValue::List(_))) => v.to_string(),
+                        Some(_) => panic!("list expected"),
+                        None => String::new(),
+                    };
+                    format!("{tags};{ints}")
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(s) = cli.exec("cmd --tag a -t b --tag=c"));
+    check!(s == "a,b,c;");
+
+    let_assert!(Ok(s) = cli.exec("cmd --tag a"));
+    check!(s == "a;");
+
+    let_assert!(Ok(s) = cli.exec("cmd --ints 1,2,-3 -i 4"));
+    check!(s == ";1,2,-3,4");
+
+    let_assert!(Err(Error::ParseInt(span, _)) = cli.exec("cmd --ints 1,x,3"));
+    check!(span.arg() == "x");
+}
+
+#[test]
+fn multiple_param_scalar_default() {
+    let cli = <Cli<Test<(Vec<i64>, Vec<String>)>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("tag")
+                        .value_type(ArgType::Int)
+                        .multiple()
+                        .default_value(ArgValue::Int(1)),
+                )
+                .parameter(
+                    Parameter::with_name("names")
+                        .value_type(ArgType::String)
+                        .delimiter(',')
+                        .default_value(ArgValue::String("all".into())),
+                )
+                .handler(|ctx| (ctx.get("tag").unwrap(), ctx.get("names").unwrap())),
+        )
+        .build();
+
+    let_assert!(Ok((tags, names)) = cli.exec("cmd"));
+    check!(tags == [1]);
+    check!(names == ["all"]);
+
+    let_assert!(Ok((tags, names)) = cli.exec("cmd --tag 2 --tag 3 --names a,b"));
+    check!(tags == [2, 3]);
+    check!(names == ["a", "b"]);
+}
+
+#[test]
+fn command_with_param_occurrences() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("tag")
+                        .value_type(ArgType::String)
+                        .alias("t")
+                        .multiple()
+                        .min_occurrences(1)
+                        .max_occurrences(2),
+                )
+                .handler(|_| {}),
+        )
+        .build();
+
+    check!(let Ok(_) = cli.exec("cmd -t a"));
+    check!(let Ok(_) = cli.exec("cmd -t a -t b"));
+
+    let_assert!(
+        Err(Error::TooManyOccurrences {
+            span,
+            parameter,
+            max
+        }) = cli.exec("cmd -t a -t b --tag c")
+    );
+    check!(span.arg() == "c");
+    check!(parameter == "tag");
+    check!(max == 2);
+
+    let_assert!(
+        Err(Error::TooFewOccurrences {
+            span,
+            parameter,
+            min
+        }) = cli.exec("cmd")
+    );
+    check!(span.arg() == "cmd");
+    check!(parameter == "tag");
+    check!(min == 1);
+}
+
+#[test]
+fn command_with_count_param() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("verbose")
+                        .value_type(ArgType::Count)
+                        .alias("v"),
+                )
+                .parameter(Parameter::with_name("flag").alias("f"))
+                .handler(
+                    |ctx| match ctx.units.last().unwrap().parameters().get("verbose") {
+                        Some((_, ArgValue::Int(v))) => *v,
+                        Some(_) => panic!("int expected"),
+                        None => 0,
+                    },
+                ),
+        )
+        .build();
+
+    let_assert!(Ok(0) = cli.exec("cmd"));
+    let_assert!(Ok(1) = cli.exec("cmd -v"));
+    let_assert!(Ok(3) = cli.exec("cmd -vfv --verbose"));
+    let_assert!(Ok(5) = cli.exec("cmd -vv -vvv"));
+    let_assert!(Ok(3) = cli.exec("cmd --verbose=2 -v"));
+    let_assert!(Ok(5) = cli.exec("cmd -vvv -v=2"));
+    let_assert!(Ok(6) = cli.exec("cmd -v --verbose=5"));
+
+    let_assert!(Err(Error::ParseInt(span, _)) = cli.exec("cmd --verbose=x"));
+    check!(span.arg() == "x");
+}
+
+#[test]
+fn command_with_negatable_param() {
+    let cli = <Cli<Test<Option<bool>>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("color")
+                        .alias("c")
+                        .negatable()
+                        .default_value(ArgValue::Bool(true)),
+                )
+                .parameter(Parameter::with_name("flag"))
+                .handler(
+                    |ctx| match ctx.units.last().unwrap().parameters().get("color") {
+                        Some((_, ArgValue::Bool(v))) => Some(*v),
+                        _ => None,
+                    },
+                ),
+        )
+        .build();
+
+    let_assert!(Ok(Some(true)) = cli.exec("cmd"));
+    let_assert!(Ok(Some(true)) = cli.exec("cmd --color"));
+    let_assert!(Ok(Some(false)) = cli.exec("cmd --no-color"));
+    let_assert!(Ok(Some(false)) = cli.exec("cmd --color --no-c"));
+
+    let_assert!(Err(Error::NotParameter { span, .. }) = cli.exec("cmd --no-flag"));
+    check!(span.arg() == "--no-flag");
+
+    let_assert!(Err(Error::NotValue(span)) = cli.exec("cmd --no-color=yes"));
+    check!(span.arg() == "yes");
+}
+
+#[test]
+fn negatable_param_help() {
+    let help_text = Rc::new(RefCell::new(String::new()));
+    let printer = TestPrinter(help_text.clone());
+    let cli = <Cli<Test<()>>>::builder()
+        .set_printer(printer)
+        .print_help(true)
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("color")
+                        .negatable()
+                        .description("Colored output"),
+                )
+                .subcommand(CommandBuilder::with_name("sub").handler(|_| {})),
+        )
+        .build();
+
+    assert!(cli.exec("cmd help").is_ok());
+    assert_eq!(
+        help_text.borrow().as_str(),
+        r"Help:
+  Parameters:
+    --[no-]color        <bool>  Colored output
+----------------------------------------
+  Subcommands:
+    help                 This help
+    sub                  "
+    );
+}
+
+#[test]
+#[should_panic]
+fn negatable_not_bool_param() {
+    let _ = <CommandBuilder<Test<()>>>::with_name("cmd").parameter(
+        Parameter::with_name("int")
+            .value_type(ArgType::Int)
+            .negatable(),
+    );
+}
+
+#[test]
+fn command_with_choice_values() {
+    let cli = <Cli<Test<(String, String)>>>::builder()
+        .command(
+            CommandBuilder::with_name("export")
+                .use_value(ArgType::choice_ignore_case(["Json", "Csv"]))
+                .parameter(
+                    Parameter::with_name("format")
+                        .value_type(ArgType::choice(["json", "csv", "table"]))
+                        .alias("f"),
+                )
+                .handler(|ctx| {
+                    let unit = ctx.units.last().unwrap();
+                    let value = unit.value().map(|v| v.to_string()).unwrap_or_default();
+                    let format = match unit.parameters().get("format") {
+                        Some((_, v)) => v.to_string(),
+                        None => String::new(),
+                    };
+                    (value, format)
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("export -f table"));
+    check!(r == (String::new(), "table".to_string()));
+
+    let_assert!(Ok(r) = cli.exec("export --format=csv JSON"));
+    check!(r == ("Json".to_string(), "csv".to_string()));
+
+    let_assert!(Err(Error::InvalidChoice { span, allowed }) = cli.exec("export -f xml"));
+    check!(span.arg() == "xml");
+    check!(allowed == ["json", "csv", "table"]);
+
+    let_assert!(Err(Error::InvalidChoice { span, .. }) = cli.exec("export -f JSON"));
+    check!(span.arg() == "JSON");
+
+    let_assert!(Err(Error::InvalidChoice { span, allowed }) = cli.exec("export xml"));
+    check!(span.arg() == "xml");
+    check!(allowed == ["Json", "Csv"]);
+}
+
+#[test]
+fn choice_param_help() {
+    let help_text = Rc::new(RefCell::new(String::new()));
+    let printer = TestPrinter(help_text.clone());
+    let cli = <Cli<Test<()>>>::builder()
+        .set_printer(printer)
+        .print_help(true)
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(
+                    Parameter::with_name("format")
+                        .value_type(ArgType::choice(["json", "csv"]))
+                        .description("Output format"),
+                )
+                .subcommand(CommandBuilder::with_name("sub").handler(|_| {})),
+        )
+        .build();
+
+    assert!(cli.exec("cmd help").is_ok());
+    assert_eq!(
+        help_text.borrow().as_str(),
+        r"Help:
+  Parameters:
+    --format            <json|csv> Output format
+----------------------------------------
+  Subcommands:
+    help                 This help
+    sub                  "
+    );
+}
+
+#[test]
+fn command_with_custom_values() {
+    use crate::FromStrParser;
+    use std::net::Ipv4Addr;
+
+    #[derive(Debug, PartialEq)]
+    struct Id(u32);
+
+    let cli = <Cli<Test<(Option<Ipv4Addr>, Option<u32>)>>>::builder()
+        .command(
+            CommandBuilder::with_name("connect")
+                .positional("ip", ArgType::custom(FromStrParser::<Ipv4Addr>::new()))
+                .parameter(Parameter::with_name("id").value_parser(|arg: &str| {
+                    match arg.strip_prefix("id") {
+                        Some(n) => n.parse().map(Id).map_err(|e| format!("{e}")),
+                        None => Err("id must start with \"id\"".to_string()),
+                    }
+                }))
+                .handler(|ctx| {
+                    let unit = ctx.units.last().unwrap();
+                    let ip = match unit.value() {
+                        Some(ArgValue::Custom(v)) => v.downcast_ref::<Ipv4Addr>().copied(),
+                        _ => None,
+                    };
+                    let id = match unit.parameters().get("id") {
+                        Some((_, ArgValue::Custom(v))) => v.downcast_ref::<Id>().map(|id| id.0),
+                        _ => None,
+                    };
+                    (ip, id)
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("connect 10.0.0.1 --id id42"));
+    check!(r == (Some(Ipv4Addr::new(10, 0, 0, 1)), Some(42)));
+
+    let_assert!(Err(Error::Custom { span, message }) = cli.exec("connect 10.0.0.256"));
+    check!(span.arg() == "10.0.0.256");
+    check!(message == "invalid IPv4 address syntax");
+
+    let_assert!(Err(Error::Custom { span, message }) = cli.exec("connect 10.0.0.1 --id=42"));
+    check!(span.arg() == "42");
+    check!(message == "id must start with \"id\"");
+}
+
+#[test]
+fn typed_access_to_values() {
+    use crate::ValueError;
+
+    let cli = <Cli<Test<()>>>::builder()
+        .command(
+            CommandBuilder::with_name("cmd")
+                .positional("float", ArgType::Float)
+                .variadic("names", ArgType::String)
+                .parameter(Parameter::with_name("flag").alias("f"))
+                .parameter(
+                    Parameter::with_name("int")
+                        .value_type(ArgType::Int)
+                        .alias("i"),
+                )
+                .parameter(
+                    Parameter::with_name("tag")
+                        .value_type(ArgType::String)
+                        .multiple(),
+                )
+                .handler(|ctx| {
+                    check!(ctx.get::<bool>("flag") == Ok(true));
+                    check!(ctx.get::<bool>("f") == Ok(true));
+                    check!(ctx.get::<i64>("int") == Ok(42));
+                    check!(ctx.get_or::<i64>("int", 0) == Ok(42));
+                    check!(ctx.value_as::<f64>() == Ok(4.2));
+                    check!(ctx.get::<f64>("float") == Ok(4.2));
+                    check!(ctx.get::<Vec<String>>("names") == Ok(vec!["a".into(), "b".into()]));
+                    check!(ctx.get::<Vec<String>>("tag") == Ok(vec!["x".into(), "y".into()]));
+                    check!(
+                        ctx.get_or::<Vec<String>>("unknown", vec![])
+                            == Err(ValueError::Unknown("unknown".into()))
+                    );
+                    check!(
+                        ctx.get::<String>("unknown") == Err(ValueError::Unknown("unknown".into()))
+                    );
+                    check!(
+                        ctx.get_or("verbos", false) == Err(ValueError::Unknown("verbos".into()))
+                    );
+                    check!(
+                        ctx.get::<String>("int")
+                            == Err(ValueError::TypeMismatch {
+                                name: "int".into(),
+                                declared: "int".into(),
+                                expected: "string"
+                            })
+                    );
+                    check!(
+                        ctx.get::<String>("tag")
+                            == Err(ValueError::TypeMismatch {
+                                name: "tag".into(),
+                                declared: "string...".into(),
+                                expected: "string"
+                            })
+                    );
+                }),
+        )
+        .command(
+            CommandBuilder::with_name("empty")
+                .parameter(Parameter::with_name("flag"))
+                .optional_positional("name", ArgType::String)
+                .handler(|ctx| {
+                    check!(ctx.get_or("flag", false) == Ok(false));
+                    check!(ctx.get_or("name", "none".to_owned()) == Ok("none".to_owned()));
+                    check!(ctx.get::<String>("name") == Err(ValueError::Missing("name".into())));
+                    check!(ctx.get::<bool>("flag") == Err(ValueError::Missing("flag".into())));
+                    check!(ctx.value_as::<i64>() == Err(ValueError::Missing("value".into())));
+                }),
+        )
+        .build();
+
+    check!(let Ok(_) = cli.exec("cmd -f --int 42 --tag x --tag y 4.2 a b"));
+    check!(let Ok(_) = cli.exec("empty"));
+}
+
+#[test]
+fn fallible_handler() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("div")
+                .positional("a", ArgType::Int)
+                .positional("b", ArgType::Int)
+                .try_handler(|ctx| {
+                    let a: i64 = ctx.get("a").unwrap();
+                    let b: i64 = ctx.get("b").unwrap();
+                    a.checked_div(b)
+                        .ok_or_else(|| "division by zero".to_string())
+                }),
+        )
+        .command(CommandBuilder::with_name("one").handler(|_| 1))
+        .print_error(true)
+        .build();
+
+    let_assert!(Ok(2) = cli.exec("div 6 3"));
+    let_assert!(Ok(1) = cli.exec("one"));
+    let_assert!(Err(e) = cli.exec("div 1 0"));
+    check!(e == Error::Handler("division by zero".to_string()));
+    check!(e.to_string() == "Handler error: division by zero");
+    let_assert!(Err(Error::ParseInt(..)) = cli.exec("div 1 x"));
+}
+
+#[test]
+fn owned_error() {
+    fn assert_send_sync<E: std::error::Error + Send + Sync + 'static>(_: &E) {}
+
+    let cli = <Cli<Test<()>>>::builder()
+        .command(CommandBuilder::with_name("cmd").use_value(ArgType::Int))
+        .build();
+
+    let error = {
+        let line = String::from("cmd 1x");
+        let_assert!(Err(e) = cli.exec(&line));
+        check!(e.span().map(|s| s.arg()) == Some("1x"));
+        e.into_owned()
+    };
+    assert_send_sync(&error);
+    check!(error.kind() == crate::ErrorKind::ParseInt);
+    check!(error.message() == "Parse int error: invalid digit found in string");
+    check!(error.line() == Some("cmd 1x"));
+    check!(error.range() == Some(4..6));
+    check!(error.arg() == Some("1x"));
+    check!(error.to_string() == error.message());
+
+    let_assert!(Err(e) = cli.exec("cmd 1 2"));
+    let error = crate::OwnedError::from(e);
+    check!(error.kind() == crate::ErrorKind::UnexpectedValue);
+    check!(error.arg() == Some("2"));
+
+    let_assert!(Err(e) = cli.exec("cmd"));
+    check!(e.span() == None);
+    let error = e.into_owned();
+    check!(error.kind() == crate::ErrorKind::NoHandler);
+    check!(error.message() == "No handler for command: cmd");
+    check!(error.line() == None);
+    check!(error.range() == None);
+}
+
+#[test]
+fn error_diagnostic() {
+    let output = Rc::new(RefCell::new(String::new()));
+    let cli = <Cli<Test<()>>>::builder()
+        .set_printer(TestPrinter(output.clone()))
+        .print_error(true)
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(Parameter::with_name("mode").value_type(ArgType::choice(["a", "b"])))
+                .parameter(Parameter::with_name("count").value_type(ArgType::Int))
+                .handler(|_| {}),
+        )
+        .build();
+
+    let_assert!(Err(_) = cli.exec("cmd --count ten"));
+    check!(
+        *output.borrow()
+            == "error: Parse int error: invalid digit found in string\n  \
+                | cmd --count ten\n  \
+                |             ^^^ not an integer\n  \
+                = hint: expected <int>"
+    );
+
+    let_assert!(Err(_) = cli.exec("cmd --mode=c"));
+    check!(
+        *output.borrow()
+            == "error: Invalid value: c. Possible values: a, b\n  \
+                | cmd --mode=c\n  \
+                |            ^ invalid value\n  \
+                = hint: expected one of: a, b"
+    );
+
+    let_assert!(Err(_) = cli.exec("cmd 'unclosed"));
+    check!(
+        *output.borrow()
+            == "error: Unterminated quote: 'unclosed\n  \
+                | cmd 'unclosed\n  \
+                |     ^^^^^^^^^ unterminated quote\n  \
+                = hint: close the quote"
+    );
+
+    let_assert!(Err(_) = cli.exec("run"));
+    check!(
+        *output.borrow()
+            == "error: Not a command: run\n  \
+                | run\n  \
+                | ^^^ unknown command"
+    );
+}
+
+#[test]
+fn error_diagnostic_color() {
+    let output = Rc::new(RefCell::new(String::new()));
+    let cli = <Cli<Test<()>>>::builder()
+        .set_printer(TestPrinter(output.clone()))
+        .print_error(true)
+        .error_color(true)
+        .command(CommandBuilder::with_name("cmd").handler(|_| {}))
+        .build();
+
+    let_assert!(Err(_) = cli.exec("cmd --all"));
+    check!(
+        *output.borrow()
+            == "\x1b[1;31merror\x1b[0m: \x1b[1mNot valid parameter: --all\x1b[0m\n  \
+                | cmd --all\n  \
+                |     \x1b[1;31m^^^^^ unknown parameter\x1b[0m"
+    );
+
+    let_assert!(Err(_) = cli.exec("cmd2"));
+    check!(output
+        .borrow()
+        .contains("\x1b[1;31m^^^^ unknown command\x1b[0m"));
+}
+
+#[test]
+fn unknown_names_suggestions() {
+    let output = Rc::new(RefCell::new(String::new()));
+    let cli = <Cli<Test<()>>>::builder()
+        .set_printer(TestPrinter(output.clone()))
+        .print_error(true)
+        .command(
+            CommandBuilder::with_name("status")
+                .alias("st")
+                .handler(|_| {}),
+        )
+        .command(CommandBuilder::with_name("stash").handler(|_| {}))
+        .command(
+            CommandBuilder::with_name("remote")
+                .subcommand(CommandBuilder::with_name("add").handler(|_| {}))
+                .subcommand(CommandBuilder::with_name("state").handler(|_| {}))
+                .subcommand(CommandBuilder::with_name("stats").handler(|_| {}))
+                .subcommand(
+                    CommandBuilder::with_name("remove")
+                        .alias("rm")
+                        .handler(|_| {}),
+                )
+                .parameter(Parameter::with_name("verbose").alias("v"))
+                .parameter(Parameter::with_name("force").negatable())
+                .handler(|_| {}),
+        )
+        .build();
+
+    let_assert!(Err(Error::NotCommand { suggestions, .. }) = cli.exec("stauts"));
+    check!(suggestions == ["status"]);
+    check!(output.borrow().ends_with("= hint: did you mean 'status'?"));
+
+    let_assert!(Err(Error::NotCommand { suggestions, .. }) = cli.exec("remote stat"));
+    check!(suggestions == ["state", "stats"]);
+    check!(output
+        .borrow()
+        .ends_with("= hint: did you mean one of: 'state', 'stats'?"));
+
+    let_assert!(Err(Error::NotCommand { suggestions, .. }) = cli.exec("remote rmv"));
+    check!(suggestions == ["rm"]);
+
+    let_assert!(Err(Error::NotCommand { suggestions, .. }) = cli.exec("xyz"));
+    check!(suggestions.is_empty());
+    check!(!output.borrow().contains("hint"));
+
+    let_assert!(Err(Error::NotParameter { suggestions, .. }) = cli.exec("remote --verbos"));
+    check!(suggestions == ["--verbose"]);
+
+    let_assert!(Err(Error::NotParameter { suggestions, .. }) = cli.exec("remote --no-forse"));
+    check!(suggestions == ["--no-force"]);
+
+    let_assert!(Err(Error::NotParameter { suggestions, .. }) = cli.exec("remote -force"));
+    check!(suggestions == ["--force"]);
+
+    let_assert!(Err(Error::NotParameter { suggestions, .. }) = cli.exec("remote --all"));
+    check!(suggestions.is_empty());
+}
+
+#[test]
+fn abbreviations() {
+    let cli = <Cli<Test<String>>>::builder()
+        .allow_abbreviations(true)
+        .command(
+            CommandBuilder::with_name("status")
+                .parameter(Parameter::with_name("verbose").alias("v"))
+                .parameter(Parameter::with_name("version"))
+                .parameter(Parameter::with_name("short").alias("s"))
+                .handler(|ctx| {
+                    let verbose = ctx.get_or("verbose", false).unwrap();
+                    let short = ctx.get_or("short", false).unwrap();
+                    format!("status {verbose} {short}")
+                }),
+        )
+        .command(CommandBuilder::with_name("stash").handler(|_| "stash".to_owned()))
+        .command(
+            CommandBuilder::with_name("remote")
+                .alias("rem")
+                .subcommand(CommandBuilder::with_name("add").handler(|_| "add".to_owned()))
+                .subcommand(CommandBuilder::with_name("remove").handler(|_| "remove".to_owned())),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("statu"));
+    check!(r == "status false false");
+    let_assert!(Ok(r) = cli.exec("stas"));
+    check!(r == "stash");
+    let_assert!(Ok(r) = cli.exec("status"));
+    check!(r == "status false false");
+    let_assert!(Ok(r) = cli.exec("status --verb --sh"));
+    check!(r == "status true true");
+    let_assert!(Ok(r) = cli.exec("status --verbose=no -s"));
+    check!(r == "status false true");
+    let_assert!(Ok(r) = cli.exec("r a"));
+    check!(r == "add");
+    let_assert!(Ok(r) = cli.exec("re remo"));
+    check!(r == "remove");
+
+    let_assert!(Err(Error::Ambiguous { span, candidates }) = cli.exec("st"));
+    check!(span.arg() == "st");
+    check!(candidates == ["stash", "status"]);
+
+    let_assert!(Ok(r) = cli.exec("rem a"));
+    check!(r == "add");
+
+    let_assert!(Err(Error::Ambiguous { span, candidates }) = cli.exec("status --ver"));
+    check!(span.arg() == "--ver");
+    check!(candidates == ["--verbose", "--version"]);
+
+    let_assert!(Err(Error::NotParameter { .. }) = cli.exec("status --x"));
+    let_assert!(Err(Error::NotCommand { .. }) = cli.exec("x"));
+}
+
+#[test]
+fn abbreviations_and_positionals() {
+    let cli = <Cli<Test<String>>>::builder()
+        .allow_abbreviations(true)
+        .print_help(true)
+        .command(
+            CommandBuilder::with_name("echo")
+                .use_value(ArgType::String)
+                .subcommand(CommandBuilder::with_name("twice").handler(|_| "twice".to_owned()))
+                .handler(|ctx| ctx.value_as::<String>().unwrap_or_default()),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("echo h"));
+    check!(r == "h");
+    let_assert!(Ok(r) = cli.exec("echo tw"));
+    check!(r == "tw");
+    let_assert!(Ok(r) = cli.exec("echo twice"));
+    check!(r == "twice");
+    let_assert!(Ok(r) = cli.exec("ec twice"));
+    check!(r == "twice");
+}
+
+#[test]
+fn abbreviations_and_empty_parameter_name() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .allow_abbreviations(true)
+        .command(
+            CommandBuilder::with_name("one")
+                .parameter(Parameter::with_name("only").value_type(ArgType::Int))
+                .handler(|ctx| ctx.get_or("only", 0).unwrap()),
+        )
+        .command(
+            CommandBuilder::with_name("two")
+                .parameter(Parameter::with_name("first").value_type(ArgType::Int))
+                .parameter(Parameter::with_name("second").value_type(ArgType::Int))
+                .handler(|_| 0),
+        )
+        .build();
+
+    let_assert!(Ok(5) = cli.exec("one --on=5"));
+    let_assert!(Err(Error::NotParameter { span, .. }) = cli.exec("one --=5"));
+    check!(span.arg() == "--");
+    let_assert!(Err(Error::NotParameter { .. }) = cli.exec("two --=5"));
+}
+
+#[test]
+fn ignore_case_non_ascii_short_parameters() {
+    let cli = <Cli<Test<String>>>::builder()
+        .ignore_case(true)
+        .allow_abbreviations(true)
+        .command(
+            CommandBuilder::with_name("cmd")
+                .parameter(Parameter::with_name("ж"))
+                .parameter(Parameter::with_name("Ж"))
+                .handler(|ctx| {
+                    let lower = ctx.get_or("ж", false).unwrap();
+                    let upper = ctx.get_or("Ж", false).unwrap();
+                    format!("{lower} {upper}")
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("cmd -Ж"));
+    check!(r == "false true");
+}
+
+#[test]
+fn abbreviations_disabled() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(
+            CommandBuilder::with_name("status")
+                .parameter(Parameter::with_name("verbose"))
+                .handler(|_| {}),
+        )
+        .build();
+
+    let_assert!(Err(Error::NotCommand { .. }) = cli.exec("stat"));
+    let_assert!(Err(Error::NotParameter { .. }) = cli.exec("status --verb"));
+}
+
+#[test]
+fn ignore_case() {
+    let cli = <Cli<Test<String>>>::builder()
+        .ignore_case(true)
+        .allow_abbreviations(true)
+        .command(
+            CommandBuilder::with_name("show")
+                .alias("ls")
+                .parameter(Parameter::with_name("verbose").alias("v"))
+                .parameter(Parameter::with_name("Version").alias("V"))
+                .parameter(Parameter::with_name("color").negatable())
+                .handler(|ctx| {
+                    let unit = ctx.command_units().last().unwrap();
+                    format!(
+                        "{} {} {} {:?}",
+                        unit.name(),
+                        ctx.get_or("verbose", false).unwrap(),
+                        ctx.get_or("Version", false).unwrap(),
+                        ctx.get::<bool>("color").ok(),
+                    )
+                }),
+        )
+        .build();
+
+    let_assert!(Ok(r) = cli.exec("Show"));
+    check!(r == "show false false None");
+    let_assert!(Ok(r) = cli.exec("SHOW --VERBOSE --version"));
+    check!(r == "show true true None");
+    let_assert!(Ok(r) = cli.exec("LS -v"));
+    check!(r == "ls true false None");
+    let_assert!(Ok(r) = cli.exec("sh -V --No-Color"));
+    check!(r == "show false true Some(false)");
+    let_assert!(Ok(r) = cli.exec("sh --Verb --COL"));
+    check!(r == "show true false Some(true)");
+
+    let_assert!(Err(Error::NotParameter { .. }) = cli.exec("show -L"));
+    let_assert!(Err(Error::Ambiguous { candidates, .. }) = cli.exec("show --VER"));
+    check!(candidates == ["--verbose", "--Version"]);
+}
+
+#[test]
+#[should_panic(expected = "command names \"Show\" and \"show\" collide when case is ignored")]
+fn ignore_case_collision() {
+    <Cli<Test<()>>>::builder()
+        .ignore_case(true)
+        .command(CommandBuilder::with_name("show").handler(|_| {}))
+        .command(CommandBuilder::with_name("Show").handler(|_| {}))
+        .build();
+}
+
+#[test]
+#[should_panic(
+    expected = "parameter names \"Verbose\" and \"verbose\" collide when case is ignored"
+)]
+fn ignore_case_parameter_collision() {
+    <Cli<Test<()>>>::builder()
+        .ignore_case(true)
+        .command(
+            CommandBuilder::with_name("show")
+                .parameter(Parameter::with_name("verbose"))
+                .parameter(Parameter::with_name("Verbose"))
+                .handler(|_| {}),
+        )
+        .build();
+}
+
+#[test]
+fn completion() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(
+            CommandBuilder::with_name("status")
+                .alias("st")
+                .handler(|_| {}),
+        )
+        .command(
+            CommandBuilder::with_name("open")
+                .positional("mode", ArgType::choice(["read", "write", "append"]))
+                .parameter(Parameter::with_name("verbose").alias("v"))
+                .parameter(Parameter::with_name("color").negatable())
+                .parameter(
+                    Parameter::with_name("format")
+                        .value_type(ArgType::choice(["json", "yaml", "toml"])),
+                )
+                .parameter(
+                    Parameter::with_name("file")
+                        .value_type(ArgType::String)
+                        .completer(|prefix: &str| {
+                            ["main.rs", "my file.txt", "lib.rs"]
+                                .iter()
+                                .filter(|f| f.starts_with(prefix))
+                                .map(|f| f.to_string())
+                                .collect()
+                        }),
+                )
+                .subcommand(CommandBuilder::with_name("append-only").handler(|_| {}))
+                .handler(|_| {}),
+        )
+        .build();
+
+    let complete = |line: &'static str| {
+        let c = cli.complete(line, line.len());
+        (c.span.arg(), c.span.begin, c.candidates)
+    };
+
+    check!(complete("") == ("", 0, vec!["open".into(), "st".into(), "status".into()]));
+    check!(complete("s") == ("s", 0, vec!["st".into(), "status".into()]));
+    check!(complete("op") == ("op", 0, vec!["open".into()]));
+    check!(complete("x") == ("x", 0, vec![]));
+
+    check!(complete("open a") == ("a", 5, vec!["append".into(), "append-only".into()]));
+    check!(
+        complete("open ")
+            == (
+                "",
+                5,
+                vec![
+                    "append".into(),
+                    "append-only".into(),
+                    "read".into(),
+                    "write".into()
+                ]
+            )
+    );
+    check!(
+        complete("open --")
+            == (
+                "--",
+                5,
+                vec![
+                    "--color".into(),
+                    "--file".into(),
+                    "--format".into(),
+                    "--no-color".into(),
+                    "--verbose".into()
+                ]
+            )
+    );
+    check!(complete("open -").2.contains(&"-v".to_string()));
+    check!(complete("open --f") == ("--f", 5, vec!["--file".into(), "--format".into()]));
+    check!(
+        complete("open --format ") == ("", 14, vec!["json".into(), "toml".into(), "yaml".into()])
+    );
+    check!(complete("open --format y") == ("y", 14, vec!["yaml".into()]));
+    check!(complete("open --format=j") == ("j", 14, vec!["json".into()]));
+    check!(complete("open --file m") == ("m", 12, vec!["main.rs".into(), "my\\ file.txt".into()]));
+    check!(complete("open --file my\\ f") == ("my\\ f", 12, vec!["my\\ file.txt".into()]));
+    check!(complete("open -- ") == ("", 8, vec!["append".into(), "read".into(), "write".into()]));
+    check!(complete("open read ") == ("", 10, vec!["append-only".into()]));
+
+    check!(complete("unknown ").2.is_empty());
+    check!(complete("open --format 'j").2.is_empty());
+
+    let line = "open --format j --verbose";
+    let c = cli.complete(line, 15);
+    check!(c.span.arg() == "j");
+    check!(c.candidates == ["json"]);
+}
+
+#[test]
+fn repl() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("add")
+                .variadic("numbers", ArgType::Int)
+                .handler(|ctx| {
+                    ctx.get_or::<Vec<i64>>("numbers", vec![])
+                        .unwrap()
+                        .iter()
+                        .sum()
+                }),
+        )
+        .build();
+
+    let results = Rc::new(RefCell::new(vec![]));
+    let errors = Rc::new(RefCell::new(vec![]));
+    let (r, e) = (results.clone(), errors.clone());
+    let mut repl = crate::Repl::new(cli)
+        .prompt("$ ")
+        .set_input("add 1 2\r\n   \nadd x\n\n  add 5  \nsub 1".as_bytes())
+        .set_output(Vec::new())
+        .on_result(move |result| r.borrow_mut().push(result))
+        .on_error(move |error| e.borrow_mut().push(error.to_string()));
+
+    let_assert!(Ok(()) = repl.run());
+    check!(*results.borrow() == [3, 5]);
+    check!(
+        *errors.borrow()
+            == [
+                "Parse int error: invalid digit found in string",
+                "Not a command: sub"
+            ]
+    );
+    check!(repl.output() == b"$ $ $ $ $ $ $ ");
+}
+
+#[test]
+fn repl_exit_commands() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(CommandBuilder::with_name("cmd").handler(|_| {}))
+        .build();
+
+    let count = Rc::new(Cell::new(0));
+    let c = count.clone();
+    let mut repl = crate::Repl::new(cli)
+        .set_input("cmd\nexit\n:q\ncmd\n".as_bytes())
+        .set_output(Vec::new())
+        .exit_commands([":q"])
+        .on_result(move |_| c.set(c.get() + 1));
+
+    let_assert!(Ok(()) = repl.run());
+    check!(count.get() == 1);
+    check!(repl.output() == b"> > > ");
+
+    let cli = <Cli<Test<()>>>::builder()
+        .command(CommandBuilder::with_name("cmd").handler(|_| {}))
+        .build();
+    let mut repl = crate::Repl::new(cli)
+        .set_input("cmd\n quit \ncmd\n".as_bytes())
+        .set_output(Vec::new());
+    let_assert!(Ok(()) = repl.run());
+    check!(repl.output() == b"> > ");
+}
+
+#[test]
+fn history() {
+    use crate::{Dedup, History};
+
+    let mut history = History::new(3);
+    check!(history.push("a"));
+    check!(!history.push("a"));
+    check!(!history.push("   "));
+    check!(history.push(" b "));
+    check!(history.push("a"));
+    check!(history.push("c"));
+    check!(history.iter().collect::<Vec<_>>() == [(2, "b"), (3, "a"), (4, "c")]);
+    check!(history.get(1) == None);
+    check!(history.get(4) == Some("c"));
+    check!(history.get(5) == None);
+
+    let mut history = History::new(10).dedup(Dedup::All).ignore_space(true);
+    for line in ["a", "b", " secret", "a", "c", "b"] {
+        history.push(line);
+    }
+    check!(history.iter().collect::<Vec<_>>() == [(3, "a"), (4, "c"), (5, "b")]);
+    check!(history.get(2) == None);
+    check!(history.get(4) == Some("c"));
+
+    let mut history = History::new(10).dedup(Dedup::Off);
+    history.push("a");
+    history.push("a");
+    check!(history.len() == 2);
+
+    let mut buffer = Vec::new();
+    let_assert!(Ok(()) = history.write_to(&mut buffer));
+    check!(buffer == b"a\na\n");
+    let mut loaded = History::new(1);
+    let_assert!(Ok(()) = loaded.read_from("x\ny\n\nz\n".as_bytes()));
+    check!(loaded.iter().collect::<Vec<_>>() == [(3, "z")]);
+}
+
+#[test]
+fn repl_history() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("add")
+                .variadic("numbers", ArgType::Int)
+                .handler(|ctx| {
+                    ctx.get_or::<Vec<i64>>("numbers", vec![])
+                        .unwrap()
+                        .iter()
+                        .sum()
+                }),
+        )
+        .build();
+
+    let results = Rc::new(RefCell::new(vec![]));
+    let r = results.clone();
+    let mut repl = crate::Repl::new(cli)
+        .prompt("")
+        .set_input("add 1\n add 2\nadd 3\n!!\n!1\n!9\nhistory\n".as_bytes())
+        .set_output(Vec::new())
+        .set_history(crate::History::default().ignore_space(true))
+        .on_result(move |result| r.borrow_mut().push(result));
+
+    let_assert!(Ok(()) = repl.run());
+    check!(*results.borrow() == [1, 2, 3, 3, 1]);
+    check!(
+        String::from_utf8_lossy(repl.output())
+            == "add 3\nadd 1\n!9: event not found\n    \
+                1  add 1\n    2  add 3\n    3  add 1\n    4  history\n"
+    );
+}
+
+#[test]
+fn repl_history_dedup() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("add")
+                .use_value(ArgType::Int)
+                .handler(|ctx| ctx.value_as::<i64>().unwrap()),
+        )
+        .build();
+    let results = Rc::new(RefCell::new(vec![]));
+    let r = results.clone();
+    let mut repl = crate::Repl::new(cli)
+        .prompt("")
+        .set_input("add 1\nadd 2\nadd 1\n!2\n!1\nhistory\n".as_bytes())
+        .set_output(Vec::new())
+        .set_history(crate::History::default().dedup(crate::Dedup::All))
+        .on_result(move |result| r.borrow_mut().push(result));
+
+    let_assert!(Ok(()) = repl.run());
+    check!(*results.borrow() == [1, 2, 1, 2]);
+    check!(
+        String::from_utf8_lossy(repl.output())
+            == "add 2\n!1: event not found\n    3  add 1\n    4  add 2\n    5  history\n"
+    );
+}
+
+#[test]
+fn repl_history_file() {
+    let path = std::env::temp_dir().join(format!("clean_cli_history_{}", std::process::id()));
+    std::fs::write(&path, "add 7\n").unwrap();
+
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("add")
+                .use_value(ArgType::Int)
+                .handler(|ctx| ctx.value_as::<i64>().unwrap()),
+        )
+        .build();
+    let results = Rc::new(RefCell::new(vec![]));
+    let r = results.clone();
+    let mut repl = crate::Repl::new(cli)
+        .set_input("!1\nadd 2\n".as_bytes())
+        .set_output(Vec::new())
+        .history_file(&path)
+        .on_result(move |result| r.borrow_mut().push(result));
+
+    let_assert!(Ok(()) = repl.run());
+    let saved = std::fs::read_to_string(&path);
+    std::fs::remove_file(&path).unwrap();
+    check!(*results.borrow() == [7, 2]);
+    check!(saved.unwrap() == "add 7\nadd 2\n");
+}
+
+#[test]
+fn hints() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(
+            CommandBuilder::with_name("copy")
+                .positional("from", ArgType::String)
+                .optional_positional("to", ArgType::String)
+                .parameter(Parameter::with_name("level").value_type(ArgType::Int))
+                .parameter(
+                    Parameter::with_name("format").value_type(ArgType::choice(["json", "yaml"])),
+                )
+                .handler(|_| {}),
+        )
+        .command(
+            CommandBuilder::with_name("sum")
+                .variadic("numbers", ArgType::Int)
+                .handler(|_| {}),
+        )
+        .build();
+
+    check!(cli.hint("") == None);
+    check!(cli.hint("c").as_deref() == Some("opy"));
+    check!(cli.hint("copy").is_none());
+    check!(cli.hint("copy ").as_deref() == Some("<from: string>"));
+    check!(cli.hint("copy a ").as_deref() == Some("[to: string]"));
+    check!(cli.hint("copy a b ").is_none());
+    check!(cli.hint("copy --lev").as_deref() == Some("el"));
+    check!(cli.hint("copy --level ").as_deref() == Some("<int>"));
+    check!(cli.hint("copy --format j").as_deref() == Some("son"));
+    check!(cli.hint("copy --format=y").as_deref() == Some("aml"));
+    check!(cli.hint("sum 1 2 ").as_deref() == Some("[numbers: int...]"));
+    check!(cli.hint("unknown ").is_none());
+}
+
+#[test]
+fn line_editor() {
+    let cli = <Cli<Test<()>>>::builder()
+        .command(CommandBuilder::with_name("status").handler(|_| {}))
+        .command(CommandBuilder::with_name("stash").handler(|_| {}))
+        .command(
+            CommandBuilder::with_name("commit")
+                .use_value(ArgType::String)
+                .handler(|_| {}),
+        )
+        .build();
+    let mut editor = crate::Editor::new();
+    let mut edit = |keys: &str| {
+        let mut output = Vec::new();
+        let line = editor.edit(&mut keys.as_bytes(), "> ", &mut output, 80, &cli);
+        (line.unwrap(), String::from_utf8(output).unwrap())
+    };
+
+    check!(edit("").0 == None);
+    check!(edit("\x04").0 == None);
+    check!(edit("abc\x03").0.as_deref() == Some(""));
+    check!(edit("world\x01hello \r").0.as_deref() == Some("hello world"));
+    check!(edit("ac\x1b[Db\x1b[C\x1b[Cd\r").0.as_deref() == Some("abcd"));
+    check!(edit("abd\x08c\x1b[H\x1b[3~\r").0.as_deref() == Some("bc"));
+    check!(edit("один два\x7f\x02\x02\x04\r").0.as_deref() == Some("один в"));
+
+    // kill and yank
+    check!(edit("one two three\x17\x01\x19 \r").0.as_deref() == Some("three one two "));
+    check!(edit("one two\x1bb\x0b\x15\x19\x19\r").0.as_deref() == Some("one one "));
+    check!(edit("one two\x1b[1;5D\x1b[1;5D\x1bf\x0b\r").0.as_deref() == Some("one"));
+
+    // completion
+    check!(edit("com\t\r").0.as_deref() == Some("commit "));
+    check!(edit("st\t\r").0.as_deref() == Some("stash"));
+    check!(edit("st\t\t\r").0.as_deref() == Some("status"));
+    check!(edit("st\t\t\t\r").0.as_deref() == Some("st"));
+    check!(edit("st\t\t\tx\t\r").0.as_deref() == Some("stx"));
+
+    // rendering
+    let (_, output) = edit("co\x1b[D\r");
+    check!(output.starts_with("\r> \x1b[J\r\x1b[2C"));
+    check!(output.contains("\r> co\x1b[90mmmit\x1b[0m\x1b[J\r\x1b[4C"));
+    check!(output.contains("\r> co\x1b[J\r\x1b[3C"));
+    check!(output.ends_with("\r> co\x1b[J\r\x1b[4C\r\n"));
+    let (_, output) = edit("xy\t");
+    check!(output.contains('\x07'));
+}
+
+#[test]
+fn line_editor_wrapped_line() {
+    let cli = <Cli<Test<()>>>::builder().build();
+    let mut editor = crate::Editor::new();
+    let mut output = Vec::new();
+    let line = editor.edit(
+        &mut "abcdefghi\x01\r".as_bytes(),
+        "> ",
+        &mut output,
+        10,
+        &cli,
+    );
+    check!(line.unwrap().as_deref() == Some("abcdefghi"));
+
+    let output = String::from_utf8(output).unwrap();
+    // full row, cursor is moved to the next one
+    check!(output.contains("\r> abcdefgh\r\n\x1b[J\r\x1b[1A"));
+    // redrawn from the first row
+    check!(output.contains("\x1b[1A\r> abcdefghi\x1b[J\r\x1b[1C"));
+    // cursor moved to the first row
+    check!(output.contains("\x1b[1A\r> abcdefghi\x1b[J\x1b[1A\r\x1b[2C"));
+    check!(output.ends_with("\r> abcdefghi\x1b[J\r\x1b[1C\r\n"));
+}
+
+/// Keys typed in bursts, escape sequence is not continued in the next burst
+struct Bursts<'a>(Vec<&'a [u8]>);
+
+impl crate::editor::KeyInput for Bursts<'_> {
+    fn read_byte(&mut self) -> std::io::Result<Option<u8>> {
+        while self.0.first().is_some_and(|burst| burst.is_empty()) {
+            self.0.remove(0);
+        }
+        self.read_escaped()
+    }
+
+    fn read_escaped(&mut self) -> std::io::Result<Option<u8>> {
+        let Some((byte, rest)) = self.0.first().and_then(|burst| burst.split_first()) else {
+            return Ok(None);
+        };
+        self.0[0] = rest;
+        Ok(Some(*byte))
+    }
+}
+
+#[test]
+fn line_editor_lone_escape() {
+    let cli = <Cli<Test<()>>>::builder().build();
+    let mut editor = crate::Editor::new();
+    let mut output = Vec::new();
+
+    let mut keys = Bursts(vec![b"one two\x1b", b"b\r"]);
+    let line = editor.edit(&mut keys, "> ", &mut output, 80, &cli);
+    check!(line.unwrap().as_deref() == Some("one twob"));
+
+    let mut keys = Bursts(vec![b"one two\x1b", b"\x1bb", b"\x1b[", b"D\r"]);
+    let line = editor.edit(&mut keys, "> ", &mut output, 80, &cli);
+    check!(line.unwrap().as_deref() == Some("one Dtwo"));
+
+    let mut keys = Bursts(vec![b"\x1b"]);
+    let line = editor.edit(&mut keys, "> ", &mut output, 80, &cli);
+    check!(line.unwrap() == None);
+}
+
+#[test]
+fn exec_script() {
+    use crate::ScriptMode;
+
+    let cli = <Cli<Test<String>>>::builder()
+        .command(
+            CommandBuilder::with_name("echo")
+                .variadic("words", ArgType::String)
+                .handler(|ctx| {
+                    ctx.get_or::<Vec<String>>("words", vec![])
+                        .unwrap()
+                        .join(" ")
+                }),
+        )
+        .command(CommandBuilder::with_name("fail").try_handler(|_| Err("failed".to_owned())))
+        .build();
+
+    let script = "\
+        # comment\n\
+        echo a b\r\n\
+        \n   \n\
+        echo one \\\n\
+        \x20 two \\\n\
+        three\n\
+        \x20 # indented comment\n\
+        echo back\\\\\n\
+        fail\n\
+        echo --unknown\n\
+        echo last \\";
+
+    let_assert!(Ok(report) = cli.exec_script(script.as_bytes(), ScriptMode::Continue));
+    check!(!report.is_ok());
+    check!(report.results == ["a b", "one two three", "back\\", "last"]);
+    let errors: Vec<_> = report.errors.iter().map(|e| e.to_string()).collect();
+    check!(
+        errors
+            == [
+                "line 10: Handler error: failed",
+                "line 11, column 6: Not valid parameter: --unknown"
+            ]
+    );
+    check!(report.errors[1].error.arg() == Some("--unknown"));
+    let kinds: Vec<_> = report.errors.iter().map(|e| e.error.kind()).collect();
+    check!(kinds == [crate::ErrorKind::Handler, crate::ErrorKind::NotParameter]);
+
+    let_assert!(Ok(report) = cli.exec_script(script.as_bytes(), ScriptMode::StopOnError));
+    check!(report.results == ["a b", "one two three", "back\\"]);
+    check!(report.errors.len() == 1);
+
+    let_assert!(Ok(report) = cli.exec_script("echo x \\\n  \"y".as_bytes(), ScriptMode::default()));
+    let_assert!([error] = report.errors.as_slice());
+    check!((error.line, error.column) == (2, Some(3)));
+
+    let script = "echo a\nfail || echo b\necho c && fail\nfail; echo d\necho e\n";
+    let_assert!(Ok(report) = cli.exec_script(script.as_bytes(), ScriptMode::StopOnError));
+    check!(report.results == ["a", "b", "c"]);
+    let_assert!([error] = report.errors.as_slice());
+    check!(error.line == 3);
+
+    let_assert!(Ok(report) = cli.exec_script(script.as_bytes(), ScriptMode::Continue));
+    check!(report.results == ["a", "b", "c", "d", "e"]);
+    check!(report.errors.len() == 1);
+}
+
+#[test]
+fn exec_file() {
+    use crate::ScriptMode;
+
+    let cli = <Cli<Test<()>>>::builder()
+        .command(CommandBuilder::with_name("cmd").handler(|_| {}))
+        .build();
+
+    let path = std::env::temp_dir().join(format!("clean_cli_script_{}", std::process::id()));
+    std::fs::write(&path, "cmd\n\ncmd\ncmd --x\n").unwrap();
+    let report = cli.exec_file(&path, ScriptMode::Continue);
+    std::fs::remove_file(&path).unwrap();
+
+    let_assert!(Ok(report) = report);
+    check!(report.results.len() == 2);
+    let_assert!([error] = report.errors.as_slice());
+    check!(error.file.as_deref() == Some(path.as_path()));
+    check!(error.to_string() == format!("{}:4:5: Not valid parameter: --x", path.display()));
+
+    let_assert!(Err(error) = cli.exec_file(&path, ScriptMode::Continue));
+    check!(error.kind() == std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn exec_chain() {
+    let log = Rc::new(RefCell::new(vec![]));
+    let (l1, l2) = (log.clone(), log.clone());
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("ok")
+                .use_value(ArgType::Int)
+                .handler(move |ctx| {
+                    let value = ctx.value_as::<i64>().unwrap_or(0);
+                    l1.borrow_mut().push(format!("ok {value}"));
+                    value
+                }),
+        )
+        .command(CommandBuilder::with_name("fail").try_handler(move |_| {
+            l2.borrow_mut().push("fail".to_owned());
+            Err("failed".to_owned())
+        }))
+        .build();
+    let run = |line: &str| {
+        log.borrow_mut().clear();
+        let results: Vec<_> = cli.exec_chain(line).into_iter().map(|r| r.ok()).collect();
+        (results, log.borrow().join(", "))
+    };
+
+    check!(run("ok 1; ok 2") == (vec![Some(1), Some(2)], "ok 1, ok 2".to_owned()));
+    check!(run("ok 1 && ok 2") == (vec![Some(1), Some(2)], "ok 1, ok 2".to_owned()));
+    check!(run("fail && ok 2") == (vec![None], "fail".to_owned()));
+    check!(run("ok 1 || ok 2") == (vec![Some(1)], "ok 1".to_owned()));
+    check!(run("fail || ok 2") == (vec![None, Some(2)], "fail, ok 2".to_owned()));
+    check!(run("fail && ok 1 || ok 2") == (vec![None, Some(2)], "fail, ok 2".to_owned()));
+    check!(run("ok 1 || ok 2 && ok 3") == (vec![Some(1), Some(3)], "ok 1, ok 3".to_owned()));
+    check!(run("fail; ok 1;") == (vec![None, Some(1)], "fail, ok 1".to_owned()));
+    check!(
+        run("ok 1;ok 2&&fail||ok 3")
+            == (
+                vec![Some(1), Some(2), None, Some(3)],
+                "ok 1, ok 2, fail, ok 3".to_owned()
+            )
+    );
+    check!(run("ok \"1;\" && ok 2") == (vec![None], "".to_owned()));
+}
+
+#[test]
+fn exec_chain_errors() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("ok")
+                .use_value(ArgType::Int)
+                .handler(|ctx| ctx.value_as::<i64>().unwrap_or(0)),
+        )
+        .build();
+
+    for (line, operator) in [
+        ("&& ok", 0),
+        ("ok 1 ;; ok 2", 6),
+        ("ok 1 ||", 5),
+        ("ok 1; && ok 2", 6),
+    ] {
+        let results = cli.exec_chain(line);
+        let_assert!([Err(Error::CommandExpected(span))] = results.as_slice());
+        check!(span.begin == operator, "{line}");
+    }
+
+    let results = cli.exec_chain("ok 1; ok '2");
+    let_assert!([Err(Error::UnterminatedQuote(_))] = results.as_slice());
+    let results = cli.exec_chain("ok 1; nope");
+    let_assert!([Ok(1), Err(Error::NotCommand { span, .. })] = results.as_slice());
+    check!(span.begin == 6);
+
+    let_assert!(Ok(2) = cli.exec("ok 1; ok 2"));
+    let_assert!(Err(Error::NotCommand { .. }) = cli.exec("ok 1 && nope"));
+    let_assert!(Err(Error::NoHandler(_)) = cli.exec(""));
+}
+
+#[test]
+fn exec_chain_success_predicate() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("code")
+                .use_value(ArgType::Int)
+                .handler(|ctx| ctx.value_as::<i64>().unwrap()),
+        )
+        .success_if(|&code| code == 0)
+        .build();
+
+    let results: Vec<_> = cli
+        .exec_chain("code 1 && code 2 || code 0 && code 3")
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect();
+    check!(results == [1, 0, 3]);
+}
+
+#[test]
+fn chain_in_repl_and_completion() {
+    let cli = <Cli<Test<i64>>>::builder()
+        .command(
+            CommandBuilder::with_name("status")
+                .use_value(ArgType::Int)
+                .handler(|ctx| ctx.value_as::<i64>().unwrap_or(0)),
+        )
+        .build();
+
+    let line = "status 1 && sta";
+    let completion = cli.complete(line, line.len());
+    check!(completion.span.arg() == "sta");
+    check!(completion.candidates == ["status"]);
+    check!(cli.hint("status 1; status ").as_deref() == Some("[value: int]"));
+
+    let results = Rc::new(RefCell::new(vec![]));
+    let r = results.clone();
+    let mut repl = crate::Repl::new(cli)
+        .set_input("status 1; status 2\n".as_bytes())
+        .set_output(Vec::new())
+        .on_result(move |result| r.borrow_mut().push(result));
+    let_assert!(Ok(()) = repl.run());
+    check!(*results.borrow() == [1, 2]);
+}
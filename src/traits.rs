@@ -1,21 +1,30 @@
-use crate::{Command, Parameter};
-use std::{fmt::Debug, marker::PhantomData};
+use crate::{Command, Diagnostic, Error, Parameter};
+use std::{
+    convert::Infallible,
+    fmt::{Debug, Display},
+    marker::PhantomData,
+};
 
 pub trait Config: Default + 'static {
     type Result: Default + Debug;
+    /// Error of handlers set with [`CommandBuilder::try_handler`](crate::CommandBuilder::try_handler)
+    type HandlerError: Debug + Display;
     type HelpFormatter: HelpFormatter<Self>;
+    type ErrorFormatter: ErrorFormatter<Self>;
     type PrinterInput;
     type Printer: Printer<Self> + Default;
 }
 
-pub struct DefaultConfig<R>(PhantomData<R>);
-impl<R: Default + Debug + 'static> Config for DefaultConfig<R> {
+pub struct DefaultConfig<R, E = Infallible>(PhantomData<(R, E)>);
+impl<R: Default + Debug + 'static, E: Debug + Display + 'static> Config for DefaultConfig<R, E> {
     type Result = R;
+    type HandlerError = E;
     type HelpFormatter = DefaultHelpFormatter;
+    type ErrorFormatter = DefaultErrorFormatter;
     type PrinterInput = String;
     type Printer = DefaultPrinter;
 }
-impl<R> Default for DefaultConfig<R> {
+impl<R, E> Default for DefaultConfig<R, E> {
     fn default() -> Self {
         Self(Default::default())
     }
@@ -54,6 +63,13 @@ where
         let mut delimiter = false;
         let mut buffer = "Help:".to_string();
 
+        if !command.positionals.is_empty() {
+            let usage: Vec<_> = command.positionals.iter().map(|p| p.usage()).collect();
+            buffer.push_str(
+                format!("\n{:TAB0$}Usage: {} {}", "", command.name, usage.join(" ")).as_str(),
+            );
+        }
+
         let params = &command.parameters;
         if !params.is_empty() {
             delimiter = true;
@@ -81,7 +97,20 @@ where
             }
 
             aliases.iter_mut().for_each(|(name, (param, aliases))| {
-                let mut a = (if name.len() > 1 { "--" } else { "-" }).to_string() + name;
+                let mut description = param.description.clone();
+                if let Some(default) = param.default.as_ref() {
+                    description.push_str(format!(" [default: {default}]").as_str());
+                }
+                if param.required {
+                    description.push_str(" (required)");
+                }
+
+                let prefix = match param.negatable {
+                    true => "--[no-]",
+                    false if name.len() > 1 => "--",
+                    false => "-",
+                };
+                let mut a = prefix.to_string() + name;
                 for n in aliases.iter() {
                     a.push(',');
                     if a.len() + n.len() >= 20 {
@@ -90,13 +119,20 @@ where
                     a.push_str(if n.len() > 1 { "--" } else { "-" });
                     a.push_str(n);
                 }
+                let mut value_type = match param.is_multiple() {
+                    true => format!("<{}>...", param.value_type),
+                    false => format!("<{}>", param.value_type),
+                };
+                if value_type.len() >= 8 {
+                    value_type.push(' ');
+                }
                 buffer.push_str(
                     format!(
                         "\n{:TAB1$}{:20}{:8}{}",
                         "",
                         a,
-                        format!("<{}>", param.value_type),
-                        param.description
+                        value_type,
+                        description.trim_start()
                     )
                     .as_str(),
                 );
@@ -123,3 +159,20 @@ where
         buffer.into()
     }
 }
+
+pub trait ErrorFormatter<T: Config> {
+    /// Format _error_, with ANSI colors if _color_ is set
+    fn format(error: &Error<T::HandlerError>, color: bool) -> T::PrinterInput;
+}
+
+/// Renders error as [`Diagnostic`] with the line and caret underline
+#[derive(Default)]
+pub struct DefaultErrorFormatter;
+impl<T: Config> ErrorFormatter<T> for DefaultErrorFormatter
+where
+    T::PrinterInput: From<String>,
+{
+    fn format(error: &Error<T::HandlerError>, color: bool) -> T::PrinterInput {
+        Diagnostic::new(error).render(color).into()
+    }
+}